@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::error::Error;
+use std::fmt;
+
+use super::*;
+
+/// Number of available and total slots of a resource.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Capacity {
+    available: u32,
+    total: u32,
+}
+
+impl Capacity {
+    pub fn available(&self) -> u32 {
+        self.available
+    }
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+    pub fn used(&self) -> u32 {
+        self.total.saturating_sub(self.available)
+    }
+}
+
+/// Names of the resources with a capacity, as in the API.
+const RESOURCES: [&str; 10] = [
+    "lights",
+    "sensors",
+    "groups",
+    "scenes",
+    "lightstates",
+    "schedules",
+    "rules",
+    "conditions",
+    "actions",
+    "resourcelinks",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct SensorCapacity {
+    #[serde(flatten)]
+    sensors: Option<Capacity>,
+    clip: Option<Capacity>,
+    zll: Option<Capacity>,
+    zgp: Option<Capacity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct SceneCapacity {
+    #[serde(flatten)]
+    scenes: Option<Capacity>,
+    lightstates: Option<Capacity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct RuleCapacity {
+    #[serde(flatten)]
+    rules: Option<Capacity>,
+    conditions: Option<Capacity>,
+    actions: Option<Capacity>,
+}
+
+/// Resources the bridge has room for.
+///
+/// Capacities the bridge doesn't report, e.g. on older firmware, are `None`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Capabilities {
+    lights: Option<Capacity>,
+    #[serde(default)]
+    sensors: SensorCapacity,
+    groups: Option<Capacity>,
+    #[serde(default)]
+    scenes: SceneCapacity,
+    schedules: Option<Capacity>,
+    #[serde(default)]
+    rules: RuleCapacity,
+    resourcelinks: Option<Capacity>,
+}
+
+/// Error of a create operation the bridge has no room for.
+#[derive(Debug)]
+pub struct BridgeFull {
+    resource: &'static str,
+    needed: u32,
+    capacity: Capacity,
+}
+
+impl BridgeFull {
+    pub fn resource(&self) -> &str {
+        self.resource
+    }
+    pub fn needed(&self) -> u32 {
+        self.needed
+    }
+    pub fn capacity(&self) -> &Capacity {
+        &self.capacity
+    }
+}
+
+impl Error for BridgeFull {}
+
+impl fmt::Display for BridgeFull {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "bridge full: {} {} needed, {} of {} available",
+            self.needed, self.resource, self.capacity.available, self.capacity.total
+        )
+    }
+}
+
+impl Capabilities {
+    pub fn get_capabilities<C: HTTPClient>(http_client: &C) -> Res<Self> {
+        let response = http_client.get("capabilities")?;
+        if response.trim_start().starts_with('[') {
+            // an error, e.g. from bridges older than API 1.15
+            response_success(&response)?;
+        }
+        Ok(serde_json::from_str(&response)?)
+    }
+
+    /// Check that the bridge has room for the resources, e.g. `("scenes", 1)`.
+    ///
+    /// Resources whose capacity the bridge doesn't report are not checked.
+    pub fn ensure_available(&self, resources: &[(&'static str, u32)]) -> Res<()> {
+        for &(resource, needed) in resources {
+            if !RESOURCES.contains(&resource) {
+                return Err(format!("unknown resource {}", resource).into());
+            }
+            let capacity = match self.capacity(resource) {
+                Some(capacity) => capacity,
+                None => continue,
+            };
+            if capacity.available < needed {
+                return Err(Box::new(BridgeFull {
+                    resource,
+                    needed,
+                    capacity: *capacity,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the capacity of a resource by its name in the API, e.g. `lightstates`.
+    ///
+    /// `None` for an unknown resource or a capacity the bridge doesn't report.
+    pub fn capacity(&self, resource: &str) -> Option<&Capacity> {
+        match resource {
+            "lights" => self.lights.as_ref(),
+            "sensors" => self.sensors.sensors.as_ref(),
+            "groups" => self.groups.as_ref(),
+            "scenes" => self.scenes.scenes.as_ref(),
+            "lightstates" => self.scenes.lightstates.as_ref(),
+            "schedules" => self.schedules.as_ref(),
+            "rules" => self.rules.rules.as_ref(),
+            "conditions" => self.rules.conditions.as_ref(),
+            "actions" => self.rules.actions.as_ref(),
+            "resourcelinks" => self.resourcelinks.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn lights(&self) -> Option<&Capacity> {
+        self.lights.as_ref()
+    }
+
+    pub fn sensors(&self) -> Option<&Capacity> {
+        self.sensors.sensors.as_ref()
+    }
+
+    pub fn clip_sensors(&self) -> Option<&Capacity> {
+        self.sensors.clip.as_ref()
+    }
+
+    pub fn zll_sensors(&self) -> Option<&Capacity> {
+        self.sensors.zll.as_ref()
+    }
+
+    pub fn zgp_sensors(&self) -> Option<&Capacity> {
+        self.sensors.zgp.as_ref()
+    }
+
+    pub fn groups(&self) -> Option<&Capacity> {
+        self.groups.as_ref()
+    }
+
+    pub fn scenes(&self) -> Option<&Capacity> {
+        self.scenes.scenes.as_ref()
+    }
+
+    /// Light states of all the scenes together.
+    pub fn lightstates(&self) -> Option<&Capacity> {
+        self.scenes.lightstates.as_ref()
+    }
+
+    pub fn schedules(&self) -> Option<&Capacity> {
+        self.schedules.as_ref()
+    }
+
+    pub fn rules(&self) -> Option<&Capacity> {
+        self.rules.rules.as_ref()
+    }
+
+    /// Conditions of all the rules together.
+    pub fn conditions(&self) -> Option<&Capacity> {
+        self.rules.conditions.as_ref()
+    }
+
+    /// Actions of all the rules together.
+    pub fn actions(&self) -> Option<&Capacity> {
+        self.rules.actions.as_ref()
+    }
+
+    pub fn resourcelinks(&self) -> Option<&Capacity> {
+        self.resourcelinks.as_ref()
+    }
+}
+
+/// Check with the bridge that it has room for the resources.
+///
+/// Nothing is checked on bridges without capabilities.
+pub(crate) fn ensure_available<C: HTTPClient>(
+    http_client: &C,
+    resources: &[(&'static str, u32)],
+) -> Res<()> {
+    match Capabilities::get_capabilities(http_client) {
+        Ok(capabilities) => capabilities.ensure_available(resources),
+        Err(e) if e.is::<HueError>() => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests_capabilities {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const CAPABILITIES: &str = r#"
+        {
+            "lights": {"available": 0, "total": 63},
+            "sensors": {
+                "available": 240, "total": 250,
+                "clip": {"available": 240, "total": 250},
+                "zll": {"available": 60, "total": 64},
+                "zgp": {"available": 60, "total": 64}
+            },
+            "groups": {"available": 60, "total": 64},
+            "scenes": {
+                "available": 172, "total": 200,
+                "lightstates": {"available": 3, "total": 2048}
+            },
+            "schedules": {"available": 95, "total": 100},
+            "rules": {
+                "available": 233, "total": 250,
+                "conditions": {"available": 1451, "total": 1500},
+                "actions": {"available": 964, "total": 1000}
+            },
+            "resourcelinks": {"available": 59, "total": 64},
+            "streaming": {"available": 1, "total": 1, "channels": 10},
+            "timezones": {"values": ["Europe/Ljubljana"]}
+        }"#;
+
+    fn client() -> HTTPClientMock {
+        let mut client = HTTPClientMock::default();
+        client
+            .responses
+            .insert("capabilities".to_owned(), CAPABILITIES.to_owned());
+        client
+    }
+
+    #[test]
+    fn get_capabilities_ok() {
+        let client = client();
+        let capabilities = Capabilities::get_capabilities(&client).unwrap();
+        assert_eq!(capabilities.groups().unwrap().used(), 4);
+        assert_eq!(capabilities.zll_sensors().unwrap().available(), 60);
+        assert_eq!(capabilities.lightstates().unwrap().total(), 2048);
+        assert_eq!(capabilities.conditions().unwrap().available(), 1451);
+        assert_eq!(capabilities.capacity("actions").unwrap().total(), 1000);
+    }
+
+    #[test]
+    fn bridge_full() {
+        let client = client();
+        let capabilities = Capabilities::get_capabilities(&client).unwrap();
+        assert!(capabilities
+            .ensure_available(&[("scenes", 1), ("lightstates", 3)])
+            .is_ok());
+        let error = capabilities
+            .ensure_available(&[("scenes", 1), ("lightstates", 4)])
+            .unwrap_err();
+        let full = error.downcast_ref::<BridgeFull>().unwrap();
+        assert_eq!(full.resource(), "lightstates");
+        assert_eq!(
+            error.to_string(),
+            "bridge full: 4 lightstates needed, 3 of 2048 available"
+        );
+        assert!(capabilities.ensure_available(&[("lights", 1)]).is_err());
+        assert!(capabilities.ensure_available(&[("bridges", 1)]).is_err());
+    }
+
+    #[test]
+    fn unknown_capacities() {
+        let mut client = HTTPClientMock::default();
+        client.responses.insert(
+            "capabilities".to_owned(),
+            r#"{"lights": {"available": 0, "total": 63}, "scenes": {"available": 0, "total": 200}}"#
+                .to_owned(),
+        );
+        let capabilities = Capabilities::get_capabilities(&client).unwrap();
+        assert!(capabilities.lightstates().is_none());
+        assert!(capabilities.rules().is_none());
+        assert!(ensure_available(&client, &[("rules", 1), ("lightstates", 10)]).is_ok());
+        assert!(ensure_available(&client, &[("scenes", 1)]).is_err());
+
+        // bridges before API 1.15 have no capabilities
+        client.responses.insert(
+            "capabilities".to_owned(),
+            r#"[{"error":{"type":4,"address":"/capabilities","description":"method, GET, not available for resource, /capabilities"}}]"#
+                .to_owned(),
+        );
+        assert!(Capabilities::get_capabilities(&client).is_err());
+        assert!(ensure_available(&client, &[("scenes", 1)]).is_ok());
+    }
+}
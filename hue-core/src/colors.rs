@@ -0,0 +1,381 @@
+//! Named colors and color space conversions for Hue lights.
+
+/// Lowest color temperature in mireds supported by Hue lights.
+pub const CT_MIN: u16 = 153;
+/// Highest color temperature in mireds supported by Hue lights.
+pub const CT_MAX: u16 = 500;
+
+/// Triangle of the CIE xy color space a light can reproduce.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Gamut {
+    red: [f32; 2],
+    green: [f32; 2],
+    blue: [f32; 2],
+}
+
+/// Gamut of older LivingColors lights.
+pub const GAMUT_A: Gamut = Gamut {
+    red: [0.704, 0.296],
+    green: [0.2151, 0.7106],
+    blue: [0.138, 0.08],
+};
+
+/// Gamut of the first generation Hue bulbs.
+pub const GAMUT_B: Gamut = Gamut {
+    red: [0.675, 0.322],
+    green: [0.409, 0.518],
+    blue: [0.167, 0.04],
+};
+
+/// Gamut of the current Hue lights.
+pub const GAMUT_C: Gamut = Gamut {
+    red: [0.6915, 0.3083],
+    green: [0.17, 0.7],
+    blue: [0.1532, 0.0475],
+};
+
+impl Gamut {
+    pub fn new(red: [f32; 2], green: [f32; 2], blue: [f32; 2]) -> Self {
+        Gamut { red, green, blue }
+    }
+
+    /// Get one of the known gamuts by its type ("A", "B" or "C").
+    pub fn from_type(ty: &str) -> Option<Self> {
+        match ty {
+            "A" => Some(GAMUT_A),
+            "B" => Some(GAMUT_B),
+            "C" => Some(GAMUT_C),
+            _ => None,
+        }
+    }
+
+    /// Check if the color is inside the gamut.
+    pub fn contains(&self, xy: [f32; 2]) -> bool {
+        let side = |a: [f32; 2], b: [f32; 2]| {
+            (b[0] - a[0]) * (xy[1] - a[1]) - (b[1] - a[1]) * (xy[0] - a[0])
+        };
+        let d1 = side(self.red, self.green);
+        let d2 = side(self.green, self.blue);
+        let d3 = side(self.blue, self.red);
+        let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(has_neg && has_pos)
+    }
+
+    /// Get the closest color inside the gamut.
+    pub fn clamp(&self, xy: [f32; 2]) -> [f32; 2] {
+        if self.contains(xy) {
+            return xy;
+        }
+        [
+            closest_point(self.red, self.green, xy),
+            closest_point(self.green, self.blue, xy),
+            closest_point(self.blue, self.red, xy),
+        ]
+        .iter()
+        .copied()
+        .min_by(|a, b| xy_distance(*a, xy).total_cmp(&xy_distance(*b, xy)))
+        .unwrap()
+    }
+}
+
+/// Closest point to `p` on the line segment from `a` to `b`.
+fn closest_point(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> [f32; 2] {
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let ap = [p[0] - a[0], p[1] - a[1]];
+    let t = ((ap[0] * ab[0] + ap[1] * ab[1]) / (ab[0] * ab[0] + ab[1] * ab[1])).clamp(0.0, 1.0);
+    [a[0] + ab[0] * t, a[1] + ab[1] * t]
+}
+
+/// Euclidean distance between two colors in the xy color space.
+pub fn xy_distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Convert a color temperature in mireds to the xy color space.
+///
+/// Uses the cubic spline approximation of the Planckian locus by Kim et al.
+pub fn ct_to_xy(ct: u16) -> [f32; 2] {
+    let t = 1_000_000.0 / f64::from(ct.clamp(40, 600));
+    let x = if t <= 4000.0 {
+        -0.266_123_9e9 / t.powi(3) - 0.234_358_9e6 / t.powi(2) + 0.877_695_6e3 / t + 0.179_910
+    } else {
+        -3.025_846_9e9 / t.powi(3) + 2.107_037_9e6 / t.powi(2) + 0.222_634_7e3 / t + 0.240_390
+    };
+    let y = if t <= 2222.0 {
+        -1.106_381_4 * x.powi(3) - 1.348_110_20 * x.powi(2) + 2.185_558_32 * x - 0.202_196_83
+    } else if t <= 4000.0 {
+        -0.954_947_6 * x.powi(3) - 1.374_185_93 * x.powi(2) + 2.091_370_15 * x - 0.167_488_67
+    } else {
+        3.081_758_0 * x.powi(3) - 5.873_386_70 * x.powi(2) + 3.751_129_97 * x - 0.370_014_83
+    };
+    [x as f32, y as f32]
+}
+
+/// Convert an sRGB color to the xy color space.
+///
+/// Uses the wide gamut conversion recommended by Philips, the result is not
+/// clamped to any light gamut. Black has no chromaticity and maps to white.
+pub fn rgb_to_xy(red: u8, green: u8, blue: u8) -> [f32; 2] {
+    let gamma = |value: u8| {
+        let value = f32::from(value) / 255.0;
+        if value > 0.04045 {
+            ((value + 0.055) / 1.055).powf(2.4)
+        } else {
+            value / 12.92
+        }
+    };
+    let (r, g, b) = (gamma(red), gamma(green), gamma(blue));
+
+    let x = r * 0.664_511 + g * 0.154_324 + b * 0.162_028;
+    let y = r * 0.283_881 + g * 0.668_433 + b * 0.047_685;
+    let z = r * 0.000_088 + g * 0.072_310 + b * 0.986_039;
+    let sum = x + y + z;
+    if sum == 0.0 {
+        return [0.3227, 0.329];
+    }
+    [x / sum, y / sum]
+}
+
+/// Color a name resolves to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NamedColor {
+    /// Color in the xy color space.
+    Xy([f32; 2]),
+    /// White color temperature in mireds.
+    Ct(u16),
+}
+
+/// Whites used by the Hue light recipes, in mireds.
+const HUE_WHITES: &[(&str, u16)] = &[
+    ("energize", 156),
+    ("concentrate", 233),
+    ("read", 346),
+    ("bright", 366),
+    ("relax", 447),
+];
+
+/// CSS (X11) color names and their sRGB values.
+const CSS_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+/// Look up a color by name.
+///
+/// Names are case insensitive and spaces, dashes and underscores are ignored,
+/// so "Cornflower Blue" and "cornflower-blue" both resolve.
+pub fn named_color(name: &str) -> Option<NamedColor> {
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase();
+
+    if let Some((_, ct)) = HUE_WHITES.iter().find(|(n, _)| *n == name) {
+        return Some(NamedColor::Ct(*ct));
+    }
+    CSS_COLORS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, rgb)| NamedColor::Xy(rgb_to_xy((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8)))
+}
+
+/// Get all the known color names.
+pub fn color_names() -> impl Iterator<Item = &'static str> {
+    HUE_WHITES
+        .iter()
+        .map(|(name, _)| *name)
+        .chain(CSS_COLORS.iter().map(|(name, _)| *name))
+}
+
+#[cfg(test)]
+mod tests_colors {
+
+    use super::*;
+
+    #[test]
+    fn rgb_to_xy_white() {
+        let xy = rgb_to_xy(255, 255, 255);
+        assert!(xy_distance(xy, [0.3227, 0.329]) < 0.001);
+    }
+
+    #[test]
+    fn ct_to_xy_locus() {
+        // 2700K and 6500K
+        assert!(xy_distance(ct_to_xy(370), [0.4599, 0.4106]) < 0.005);
+        assert!(xy_distance(ct_to_xy(153), [0.3135, 0.3237]) < 0.005);
+    }
+
+    #[test]
+    fn gamut_clamp() {
+        assert!(GAMUT_C.contains([0.4, 0.4]));
+        assert_eq!(GAMUT_C.clamp([0.4, 0.4]), [0.4, 0.4]);
+
+        let red = rgb_to_xy(255, 0, 0);
+        assert!(!GAMUT_B.contains(red));
+        let clamped = GAMUT_B.clamp(red);
+        assert!(xy_distance(clamped, GAMUT_B.red) < 0.01);
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(named_color("Relax"), Some(NamedColor::Ct(447)));
+        assert_eq!(named_color("energize"), Some(NamedColor::Ct(156)));
+        assert_eq!(
+            named_color("Cornflower Blue"),
+            named_color("cornflowerblue")
+        );
+        match named_color("coral") {
+            Some(NamedColor::Xy(xy)) => assert!(xy[0] > 0.5 && xy[1] > 0.3),
+            other => panic!("unexpected color {:?}", other),
+        }
+        assert_eq!(named_color("no such color"), None);
+        assert!(color_names().any(|name| name == "read"));
+    }
+}
@@ -0,0 +1,589 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::timezones::BridgeTime;
+use super::*;
+
+/// ZigBee channels the bridge can use.
+pub const ZIGBEE_CHANNELS: [u8; 4] = [11, 15, 20, 25];
+
+/// Connection of the bridge to the portal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PortalState {
+    signedon: bool,
+    incoming: bool,
+    outgoing: bool,
+    communication: String,
+}
+
+impl PortalState {
+    pub fn signedon(&self) -> bool {
+        self.signedon
+    }
+    pub fn incoming(&self) -> bool {
+        self.incoming
+    }
+    pub fn outgoing(&self) -> bool {
+        self.outgoing
+    }
+    pub fn communication(&self) -> &str {
+        &self.communication
+    }
+}
+
+/// Status of the internet services, `connected` or `disconnected`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InternetServices {
+    internet: String,
+    remoteaccess: String,
+    time: String,
+    swupdate: String,
+}
+
+impl InternetServices {
+    pub fn internet(&self) -> &str {
+        &self.internet
+    }
+    pub fn remoteaccess(&self) -> &str {
+        &self.remoteaccess
+    }
+    pub fn time(&self) -> &str {
+        &self.time
+    }
+    pub fn swupdate(&self) -> &str {
+        &self.swupdate
+    }
+}
+
+/// Application registered as a user of the bridge API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WhitelistEntry {
+    name: String,
+    #[serde(rename = "create date", with = "crate::timestamp", default)]
+    create_date: Option<NaiveDateTime>,
+    #[serde(rename = "last use date", with = "crate::timestamp", default)]
+    last_use_date: Option<NaiveDateTime>,
+}
+
+impl WhitelistEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn create_date(&self) -> Option<NaiveDateTime> {
+        self.create_date
+    }
+    pub fn last_use_date(&self) -> Option<NaiveDateTime> {
+        self.last_use_date
+    }
+    /// Time of the last use in UTC, the create date if the key was never used.
+    pub fn last_used(&self) -> Option<NaiveDateTime> {
+        self.last_use_date.or(self.create_date)
+    }
+}
+
+/// Writable attributes of the bridge configuration, only the set ones are sent.
+#[derive(Builder, Serialize, Default, Debug, Clone)]
+#[builder(default)]
+#[builder(setter(into))]
+pub struct ConfigUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    zigbeechannel: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dhcp: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipaddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    netmask: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxyaddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxyport: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BridgeConfig<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    name: String,
+    #[serde(default)]
+    bridgeid: Option<String>,
+    #[serde(default)]
+    modelid: Option<String>,
+    mac: String,
+    zigbeechannel: u8,
+    dhcp: bool,
+    ipaddress: String,
+    netmask: String,
+    gateway: String,
+    proxyaddress: String,
+    proxyport: u16,
+    #[serde(rename = "UTC", with = "crate::timestamp", default)]
+    utc: Option<NaiveDateTime>,
+    #[serde(with = "crate::timestamp", default)]
+    localtime: Option<NaiveDateTime>,
+    timezone: String,
+    apiversion: String,
+    swversion: String,
+    #[serde(default)]
+    datastoreversion: Option<String>,
+    linkbutton: bool,
+    #[serde(default)]
+    portalservices: bool,
+    #[serde(default)]
+    portalconnection: Option<String>,
+    #[serde(default)]
+    portalstate: Option<PortalState>,
+    #[serde(default)]
+    internetservices: Option<InternetServices>,
+    #[serde(default)]
+    factorynew: bool,
+    #[serde(default)]
+    replacesbridgeid: Option<String>,
+    #[serde(default)]
+    starterkitid: String,
+    #[serde(default)]
+    whitelist: BTreeMap<String, WhitelistEntry>,
+}
+
+/// API for the configuration of the bridge.
+impl<'a, C: HTTPClient + Default> BridgeConfig<'a, C> {
+    pub fn get_config(http_client: &'a C) -> Res<Self> {
+        let response = http_client.get("config")?;
+        let mut config: Self = serde_json::from_str(&response)?;
+        config.client = Some(http_client);
+        Ok(config)
+    }
+
+    /// Send the set attributes and get the updated configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hue_core::config::*;
+    /// use hue_core::*;
+    ///
+    /// fn example<C: HTTPClient + Default>(client: &C) {
+    ///     let config = BridgeConfig::get_config(client).unwrap();
+    ///     let update = ConfigUpdateBuilder::default()
+    ///         .name(Some("Living room bridge".to_owned()))
+    ///         .build()
+    ///         .unwrap();
+    ///     config.update(&update).unwrap();
+    /// }
+    /// ```
+    pub fn update(self, update: &ConfigUpdate) -> Res<Self> {
+        if let Some(channel) = update.zigbeechannel {
+            if !ZIGBEE_CHANNELS.contains(&channel) {
+                return Err(format!(
+                    "invalid zigbee channel {}, expected one of {:?}",
+                    channel, ZIGBEE_CHANNELS
+                )
+                .into());
+            }
+        }
+        if update
+            .name
+            .as_ref()
+            .is_some_and(|name| !(4..=16).contains(&name.chars().count()))
+        {
+            return Err("bridge name must be 4 to 16 characters".into());
+        }
+        let body = serde_json::to_string(update)?;
+        let response = self.client().put("config", body)?;
+        response_success(&response)?;
+        Self::get_config(self.client.unwrap())
+    }
+
+    /// Press the link button virtually, new users can be created for 30 seconds.
+    pub fn press_link_button(&mut self) -> Res<&mut Self> {
+        let response = self
+            .client()
+            .put("config", json!({ "linkbutton": true }).to_string())?;
+        response_success(&response)?;
+        self.linkbutton = true;
+        Ok(self)
+    }
+
+    /// Make the bridge search for a light close to it and add it, even if
+    /// the light is paired with another bridge.
+    pub fn touchlink(&self) -> Res<()> {
+        let response = self
+            .client()
+            .put("config", json!({ "touchlink": true }).to_string())?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Get the current configuration from the bridge.
+    pub fn refresh(self) -> Res<Self> {
+        Self::get_config(self.client.unwrap())
+    }
+
+    /// Remove a user from the whitelist.
+    ///
+    /// The user the client is using can't be deleted.
+    pub fn delete_user(&mut self, key: &str) -> Res<&mut Self> {
+        if self.client().username() == Some(key) {
+            return Err(format!("refusing to delete {}, it is the current user", key).into());
+        }
+        let response = self.client().delete(&format!("config/whitelist/{}", key))?;
+        response_success(&response)?;
+        self.whitelist.remove(key);
+        Ok(self)
+    }
+
+    /// Get the keys of the users not used for the given time, except the current user.
+    ///
+    /// The time is measured against the bridge's clock.
+    pub fn unused_users(&self, unused_for: Duration) -> Res<Vec<&str>> {
+        let utc = self.utc.ok_or("the bridge time is not set")?;
+        let cutoff = utc - chrono::Duration::from_std(unused_for)?;
+        Ok(self
+            .whitelist
+            .iter()
+            .filter(|(key, _)| self.client().username() != Some(key.as_str()))
+            .filter(|(_, entry)| entry.last_used().is_none_or(|time| time < cutoff))
+            .map(|(key, _)| key.as_str())
+            .collect())
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `None` on older bridges, as are the other optional attributes.
+    pub fn bridgeid(&self) -> Option<&str> {
+        self.bridgeid.as_deref()
+    }
+
+    pub fn modelid(&self) -> Option<&str> {
+        self.modelid.as_deref()
+    }
+
+    pub fn mac(&self) -> &str {
+        &self.mac
+    }
+
+    pub fn zigbeechannel(&self) -> u8 {
+        self.zigbeechannel
+    }
+
+    pub fn dhcp(&self) -> bool {
+        self.dhcp
+    }
+
+    pub fn ipaddress(&self) -> &str {
+        &self.ipaddress
+    }
+
+    pub fn netmask(&self) -> &str {
+        &self.netmask
+    }
+
+    pub fn gateway(&self) -> &str {
+        &self.gateway
+    }
+
+    /// Proxy address, `none` if no proxy is used.
+    pub fn proxyaddress(&self) -> &str {
+        &self.proxyaddress
+    }
+
+    pub fn proxyport(&self) -> u16 {
+        self.proxyport
+    }
+
+    /// Current time of the bridge in UTC, `None` if the bridge has no time yet.
+    pub fn utc(&self) -> Option<NaiveDateTime> {
+        self.utc
+    }
+
+    /// Current time of the bridge in its timezone.
+    pub fn localtime(&self) -> Option<NaiveDateTime> {
+        self.localtime
+    }
+
+    pub fn timezone(&self) -> &str {
+        &self.timezone
+    }
+
+    /// Current time and timezone of the bridge.
+    ///
+    /// Fails if the bridge has no time yet, e.g. before it synchronized it.
+    pub fn time(&self) -> Res<BridgeTime> {
+        match (self.utc, self.localtime) {
+            (Some(utc), Some(localtime)) => BridgeTime::new(utc, localtime, &self.timezone),
+            _ => Err("the bridge time is not set".into()),
+        }
+    }
+
+    pub fn apiversion(&self) -> &str {
+        &self.apiversion
+    }
+
+    pub fn swversion(&self) -> &str {
+        &self.swversion
+    }
+
+    pub fn datastoreversion(&self) -> Option<&str> {
+        self.datastoreversion.as_deref()
+    }
+
+    /// Whether the link button was pressed in the last 30 seconds.
+    pub fn linkbutton(&self) -> bool {
+        self.linkbutton
+    }
+
+    pub fn portalservices(&self) -> bool {
+        self.portalservices
+    }
+
+    pub fn portalconnection(&self) -> Option<&str> {
+        self.portalconnection.as_deref()
+    }
+
+    pub fn portalstate(&self) -> Option<&PortalState> {
+        self.portalstate.as_ref()
+    }
+
+    pub fn internetservices(&self) -> Option<&InternetServices> {
+        self.internetservices.as_ref()
+    }
+
+    pub fn factorynew(&self) -> bool {
+        self.factorynew
+    }
+
+    pub fn replacesbridgeid(&self) -> Option<&str> {
+        self.replacesbridgeid.as_deref()
+    }
+
+    /// Users of the API by key.
+    pub fn whitelist(&self) -> &BTreeMap<String, WhitelistEntry> {
+        &self.whitelist
+    }
+}
+
+#[cfg(test)]
+mod tests_config {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const CONFIG: &str = r#"
+        {
+            "name": "Philips hue",
+            "zigbeechannel": 15,
+            "bridgeid": "001788FFFE23BFC2",
+            "mac": "00:17:88:23:bf:c2",
+            "dhcp": true,
+            "ipaddress": "192.168.1.7",
+            "netmask": "255.255.255.0",
+            "gateway": "192.168.1.1",
+            "proxyaddress": "none",
+            "proxyport": 0,
+            "UTC": "2019-06-02T17:24:41",
+            "localtime": "2019-06-02T19:24:41",
+            "timezone": "Europe/Ljubljana",
+            "modelid": "BSB002",
+            "datastoreversion": "70",
+            "swversion": "1931140050",
+            "apiversion": "1.31.0",
+            "swupdate2": {"checkforupdate": false, "state": "noupdates"},
+            "linkbutton": false,
+            "portalservices": true,
+            "portalconnection": "connected",
+            "portalstate": {"signedon": true, "incoming": false, "outgoing": true, "communication": "disconnected"},
+            "internetservices": {"internet": "connected", "remoteaccess": "connected", "time": "connected", "swupdate": "connected"},
+            "factorynew": false,
+            "replacesbridgeid": null,
+            "starterkitid": "",
+            "whitelist": {
+                "username": {"last use date": "2019-06-02T17:24:41", "create date": "2018-01-01T10:00:00", "name": "hue-rs"},
+                "0a1b2c": {"last use date": "2018-02-01T10:00:00", "create date": "2018-01-01T10:00:00", "name": "Hue#iPhone"},
+                "3d4e5f": {"last use date": "2019-05-01T10:00:00", "create date": "2018-01-01T10:00:00", "name": "Hue#iPad"},
+                "6a7b8c": {"last use date": "none", "create date": "2018-06-01T10:00:00", "name": "test"}
+            }
+        }"#;
+
+    #[test]
+    fn get_config_ok() {
+        let client = HTTPClientMock {
+            return_string: Some(CONFIG.to_owned()),
+            ..Default::default()
+        };
+        let config = BridgeConfig::get_config(&client).unwrap();
+        assert_eq!(config.zigbeechannel(), 15);
+        assert_eq!(config.timezone(), "Europe/Ljubljana");
+        assert_eq!(timestamp::format(config.utc()), "2019-06-02T17:24:41");
+        assert_eq!(
+            config.time().unwrap().now().to_rfc3339(),
+            "2019-06-02T19:24:41+02:00"
+        );
+        assert!(config.portalstate().unwrap().signedon());
+        assert_eq!(
+            config.internetservices().unwrap().remoteaccess(),
+            "connected"
+        );
+        assert_eq!(config.replacesbridgeid(), None);
+    }
+
+    #[test]
+    fn get_old_config_ok() {
+        let mut config: serde_json::Value = serde_json::from_str(CONFIG).unwrap();
+        for field in [
+            "bridgeid",
+            "modelid",
+            "datastoreversion",
+            "portalservices",
+            "portalconnection",
+            "portalstate",
+            "internetservices",
+            "factorynew",
+            "replacesbridgeid",
+            "starterkitid",
+        ]
+        .iter()
+        {
+            config.as_object_mut().unwrap().remove(*field);
+        }
+        let client = HTTPClientMock {
+            return_string: Some(config.to_string()),
+            ..Default::default()
+        };
+        let config = BridgeConfig::get_config(&client).unwrap();
+        assert_eq!(config.bridgeid(), None);
+        assert_eq!(config.portalstate(), None);
+        assert!(!config.factorynew());
+    }
+
+    #[test]
+    fn get_config_without_time() {
+        let client = HTTPClientMock {
+            return_string: Some(
+                CONFIG
+                    .replace(r#""UTC": "2019-06-02T17:24:41""#, r#""UTC": "none""#)
+                    .replace(r#""2019-06-02T19:24:41""#, r#""none""#),
+            ),
+            ..Default::default()
+        };
+        let config = BridgeConfig::get_config(&client).unwrap();
+        assert_eq!(config.utc(), None);
+        assert!(config.time().is_err());
+        assert!(config.unused_users(Duration::from_secs(3600)).is_err());
+    }
+
+    #[test]
+    fn update_config() {
+        let client = HTTPClientMock {
+            body: Some(r#"[{"success":{"/config/name":"Hall bridge"}}]"#.to_owned()),
+            return_string: Some(CONFIG.to_owned()),
+            ..Default::default()
+        };
+        let config = BridgeConfig::get_config(&client).unwrap();
+        let update = ConfigUpdateBuilder::default()
+            .zigbeechannel(Some(12))
+            .build()
+            .unwrap();
+        let error = config.update(&update).unwrap_err();
+        assert!(error.to_string().contains("zigbee channel"));
+
+        let config = BridgeConfig::get_config(&client).unwrap();
+        let update = ConfigUpdateBuilder::default()
+            .name(Some("Küche".to_owned()))
+            .build()
+            .unwrap();
+        config.update(&update).unwrap();
+
+        let config = BridgeConfig::get_config(&client).unwrap();
+        let update = ConfigUpdateBuilder::default()
+            .name(Some("Hall bridge".to_owned()))
+            .zigbeechannel(Some(25))
+            .timezone(Some("Europe/Berlin".to_owned()))
+            .build()
+            .unwrap();
+        config.update(&update).unwrap();
+        let requests = client.requests.borrow();
+        let (_, _, body) = requests
+            .iter()
+            .rfind(|(method, _, _)| method == "PUT")
+            .unwrap();
+        assert_eq!(
+            body,
+            r#"{"name":"Hall bridge","zigbeechannel":25,"timezone":"Europe/Berlin"}"#
+        );
+    }
+
+    #[test]
+    fn whitelist_users() {
+        let client = HTTPClientMock {
+            body: Some(r#"[{"success":"/config/whitelist/0a1b2c deleted"}]"#.to_owned()),
+            return_string: Some(CONFIG.to_owned()),
+            username: Some("username".to_owned()),
+            ..Default::default()
+        };
+        let mut config = BridgeConfig::get_config(&client).unwrap();
+        assert_eq!(config.whitelist().len(), 4);
+        assert_eq!(config.whitelist()["0a1b2c"].name(), "Hue#iPhone");
+
+        let unused = config
+            .unused_users(Duration::from_secs(180 * 24 * 3600))
+            .unwrap();
+        assert_eq!(unused, vec!["0a1b2c", "6a7b8c"]);
+        let unused = config.unused_users(Duration::from_secs(24 * 3600)).unwrap();
+        assert_eq!(unused, vec!["0a1b2c", "3d4e5f", "6a7b8c"]);
+
+        assert!(config.delete_user("username").is_err());
+        config.delete_user("0a1b2c").unwrap();
+        assert_eq!(config.whitelist().len(), 3);
+        assert_eq!(
+            client.requests.borrow().last().unwrap(),
+            &(
+                "DELETE".to_owned(),
+                "config/whitelist/0a1b2c".to_owned(),
+                "".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn link_button() {
+        let client = HTTPClientMock {
+            body: Some(r#"[{"success":{"/config/linkbutton":true}}]"#.to_owned()),
+            return_string: Some(CONFIG.to_owned()),
+            ..Default::default()
+        };
+        let mut config = BridgeConfig::get_config(&client).unwrap();
+        config.press_link_button().unwrap();
+        assert!(config.linkbutton());
+        config.touchlink().unwrap();
+
+        let config = config.refresh().unwrap();
+        assert!(!config.linkbutton());
+
+        let bodies: Vec<String> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, _, body)| body.clone())
+            .collect();
+        assert_eq!(
+            bodies,
+            vec![r#"{"linkbutton":true}"#, r#"{"touchlink":true}"#]
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+
+use super::lights::{Light, LightId};
+use super::*;
+
+string_enum! {
+    /// Software update state of the bridge or a light.
+    pub enum UpdateState {
+        NoUpdates => "noupdates",
+        NotUpdatable => "notupdatable",
+        /// Update is being downloaded.
+        Transferring => "transferring",
+        /// Light has an update downloaded and ready.
+        ReadyToInstall => "readytoinstall",
+        /// Bridge or some of the lights have an update ready.
+        AnyReadyToInstall => "anyreadytoinstall",
+        /// Bridge and all the lights have an update ready.
+        AllReadyToInstall => "allreadytoinstall",
+        Installing => "installing",
+    }
+}
+
+impl UpdateState {
+    /// Whether an update is pending or in progress.
+    pub fn pending(&self) -> bool {
+        matches!(
+            self,
+            UpdateState::Transferring
+                | UpdateState::ReadyToInstall
+                | UpdateState::AnyReadyToInstall
+                | UpdateState::AllReadyToInstall
+                | UpdateState::Installing
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BridgeUpdate {
+    state: UpdateState,
+    #[serde(with = "crate::timestamp", default)]
+    lastinstall: Option<NaiveDateTime>,
+}
+
+impl BridgeUpdate {
+    pub fn state(&self) -> &UpdateState {
+        &self.state
+    }
+
+    pub fn lastinstall(&self) -> Option<NaiveDateTime> {
+        self.lastinstall
+    }
+}
+
+/// Window in which the bridge installs updates automatically.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct AutoInstall {
+    on: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updatetime: Option<String>, // "T14:00:00"
+}
+
+impl AutoInstall {
+    pub fn on(&self) -> bool {
+        self.on
+    }
+
+    pub fn updatetime(&self) -> Option<&str> {
+        self.updatetime.as_deref()
+    }
+}
+
+/// Software update status of the bridge and its lights (`swupdate2` of the bridge config).
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Firmware<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    checkforupdate: bool,
+    #[serde(with = "crate::timestamp", default)]
+    lastchange: Option<NaiveDateTime>,
+    bridge: BridgeUpdate,
+    state: UpdateState,
+    #[serde(default)]
+    autoinstall: AutoInstall,
+}
+
+/// API for software updates of the bridge and the lights.
+impl<'a, C: HTTPClient + Default> Firmware<'a, C> {
+    pub fn get_firmware(http_client: &'a C) -> Res<Self> {
+        let response = http_client.get("config")?;
+        let mut config: serde_json::Value = serde_json::from_str(&response)?;
+        let swupdate = match config.get_mut("swupdate2") {
+            Some(swupdate) => swupdate.take(),
+            None => return Err("firmware update status not supported by this bridge".into()),
+        };
+        let mut firmware: Self = serde_json::from_value(swupdate)?;
+        firmware.client = Some(http_client);
+        Ok(firmware)
+    }
+
+    /// Get the lights with an update that is downloading, ready or installing.
+    pub fn pending_lights(&self) -> Res<BTreeMap<LightId, Light<'a, C>>> {
+        let mut lights = Light::get_lights(self.client.unwrap())?;
+        lights.retain(|_, light| light.swupdate().state().pending());
+        Ok(lights)
+    }
+
+    /// Make the bridge check for updates of itself and the lights.
+    pub fn check_for_update(&mut self) -> Res<&mut Self> {
+        self.put_swupdate(json!({ "checkforupdate": true }))?;
+        self.checkforupdate = true;
+        Ok(self)
+    }
+
+    /// Install all the updates that are ready.
+    ///
+    /// Fails unless the state is `anyreadytoinstall` or `allreadytoinstall`.
+    pub fn install(&mut self) -> Res<&mut Self> {
+        if !matches!(
+            self.state,
+            UpdateState::AnyReadyToInstall | UpdateState::AllReadyToInstall
+        ) {
+            return Err(format!("no updates to install, update state is {}", self.state).into());
+        }
+        self.put_swupdate(json!({ "install": true }))?;
+        Ok(self)
+    }
+
+    /// Configure automatic installation of updates.
+    ///
+    /// `updatetime` is the start of the one hour update window in bridge local
+    /// time, formatted as `T03:00:00`.
+    pub fn set_auto_install(&mut self, on: bool, updatetime: Option<&str>) -> Res<&mut Self> {
+        if let Some(time) = updatetime {
+            if !valid_updatetime(time) {
+                return Err(format!("invalid update time {}, expected Thh:mm:ss", time).into());
+            }
+        }
+        let autoinstall = AutoInstall {
+            on,
+            updatetime: updatetime.map(|time| time.to_owned()),
+        };
+        self.put_swupdate(json!({ "autoinstall": &autoinstall }))?;
+        if autoinstall.updatetime.is_none() {
+            self.autoinstall.on = on;
+        } else {
+            self.autoinstall = autoinstall;
+        }
+        Ok(self)
+    }
+
+    fn put_swupdate(&self, swupdate: serde_json::Value) -> Res<()> {
+        let body = json!({ "swupdate2": swupdate });
+        let response = self.client().put("config", body.to_string())?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Get the current update status from the bridge.
+    pub fn refresh(self) -> Res<Self> {
+        Self::get_firmware(self.client.unwrap())
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    /// Whether the bridge is checking for updates.
+    pub fn checkforupdate(&self) -> bool {
+        self.checkforupdate
+    }
+
+    pub fn lastchange(&self) -> Option<NaiveDateTime> {
+        self.lastchange
+    }
+
+    /// Update state of the bridge itself.
+    pub fn bridge(&self) -> &BridgeUpdate {
+        &self.bridge
+    }
+
+    /// Combined update state of the bridge and the lights.
+    pub fn state(&self) -> &UpdateState {
+        &self.state
+    }
+
+    pub fn autoinstall(&self) -> &AutoInstall {
+        &self.autoinstall
+    }
+}
+
+fn valid_updatetime(time: &str) -> bool {
+    let parts: Vec<&str> = match time.strip_prefix('T') {
+        Some(time) => time.split(':').collect(),
+        None => return false,
+    };
+    let limits = [24, 60, 60];
+    parts.len() == 3
+        && parts
+            .iter()
+            .zip(limits.iter())
+            .all(|(part, limit)| part.len() == 2 && part.parse::<u8>().is_ok_and(|v| v < *limit))
+}
+
+#[cfg(test)]
+mod tests_firmware {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const CONFIG: &str = r#"
+        {
+            "name": "Philips hue",
+            "swupdate2": {
+                "checkforupdate": false,
+                "lastchange": "2019-03-13T07:17:10",
+                "bridge": {
+                    "state": "noupdates",
+                    "lastinstall": "2019-03-07T10:47:47"
+                },
+                "state": "anyreadytoinstall",
+                "autoinstall": {
+                    "updatetime": "T14:00:00",
+                    "on": true
+                }
+            }
+        }"#;
+
+    const LIGHTS: &str = r#"
+        {
+            "1": {
+                "state": {"on": false, "reachable": true},
+                "swupdate": {"state": "readytoinstall", "lastinstall": null},
+                "type": "Dimmable light",
+                "name": "Hall",
+                "modelid": "LWB010",
+                "manufacturername": "Philips",
+                "productname": "Hue white lamp",
+                "uniqueid": "00:17:88:01:02:24:3a:e8-0b",
+                "swversion": "1.46.13_r26312"
+            },
+            "2": {
+                "state": {"on": false, "reachable": true},
+                "swupdate": {"state": "noupdates", "lastinstall": null},
+                "type": "Dimmable light",
+                "name": "Kitchen",
+                "modelid": "LWB010",
+                "manufacturername": "Philips",
+                "productname": "Hue white lamp",
+                "uniqueid": "00:17:88:01:02:24:3a:e9-0b",
+                "swversion": "1.46.13_r26312"
+            }
+        }"#;
+
+    fn client() -> HTTPClientMock {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"/config/swupdate2/install":true}}]"#.to_owned()),
+            ..Default::default()
+        };
+        client
+            .responses
+            .insert("config".to_owned(), CONFIG.to_owned());
+        client
+            .responses
+            .insert("lights".to_owned(), LIGHTS.to_owned());
+        client
+    }
+
+    #[test]
+    fn get_firmware_ok() {
+        let client = client();
+        let firmware = Firmware::get_firmware(&client).unwrap();
+        assert_eq!(firmware.state(), &UpdateState::AnyReadyToInstall);
+        assert_eq!(firmware.bridge().state(), &UpdateState::NoUpdates);
+        assert_eq!(
+            firmware.bridge().lastinstall(),
+            timestamp::parse("2019-03-07T10:47:47")
+        );
+        assert!(firmware.autoinstall().on());
+        assert_eq!(firmware.autoinstall().updatetime(), Some("T14:00:00"));
+
+        let pending = firmware.pending_lights().unwrap();
+        assert_eq!(pending.keys().collect::<Vec<_>>(), vec!["1"]);
+    }
+
+    #[test]
+    fn firmware_requests() {
+        let client = client();
+        let mut firmware = Firmware::get_firmware(&client).unwrap();
+        firmware.check_for_update().unwrap();
+        firmware.install().unwrap();
+        assert!(firmware.set_auto_install(true, Some("3:00")).is_err());
+        firmware.set_auto_install(true, Some("T03:00:00")).unwrap();
+
+        let bodies: Vec<String> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, _, body)| body.clone())
+            .collect();
+        assert_eq!(
+            bodies,
+            vec![
+                r#"{"swupdate2":{"checkforupdate":true}}"#,
+                r#"{"swupdate2":{"install":true}}"#,
+                r#"{"swupdate2":{"autoinstall":{"on":true,"updatetime":"T03:00:00"}}}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn firmware_errors() {
+        let mut failing = client();
+        failing.body = Some(
+            r#"[{"error":{"type":7,"address":"/config/swupdate2/install","description":"invalid value"}}]"#
+                .to_owned(),
+        );
+        let mut firmware = Firmware::get_firmware(&failing).unwrap();
+        assert!(firmware.check_for_update().is_err());
+        assert!(firmware.install().is_err());
+        assert!(firmware.set_auto_install(false, None).is_err());
+
+        for state in ["installing", "transferring", "noupdates"].iter() {
+            let mut client = client();
+            client.responses.insert(
+                "config".to_owned(),
+                CONFIG.replace(
+                    r#""state": "anyreadytoinstall""#,
+                    &format!(r#""state": "{}""#, state),
+                ),
+            );
+            let mut firmware = Firmware::get_firmware(&client).unwrap();
+            assert!(firmware.install().is_err(), "{}", state);
+        }
+
+        let client = HTTPClientMock {
+            return_string: Some(r#"{"name": "Philips hue"}"#.to_owned()),
+            ..Default::default()
+        };
+        let error = Firmware::get_firmware(&client).unwrap_err();
+        assert!(error.to_string().contains("not supported"));
+    }
+}
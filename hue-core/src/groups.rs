@@ -0,0 +1,705 @@
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use super::lights::{Light, LightId, Tolerance};
+use super::resource::Resource;
+use super::scenes::{Scene, SceneId};
+use super::sensors::SensorId;
+use super::*;
+
+string_id! {
+    /// ID of a group, e.g. `1`.
+    pub struct GroupId;
+}
+
+impl GroupId {
+    /// Group 0, all the lights of the bridge.
+    pub fn all() -> Self {
+        GroupId::from(0)
+    }
+}
+
+pub type GroupAction = lights::LightState;
+
+string_enum! {
+    /// Kind of a group.
+    pub enum GroupType {
+        /// Multisource luminaire, a product made of several lights.
+        Luminaire => "Luminaire",
+        /// Lights of a luminaire, part of a `Luminaire` group.
+        Lightsource => "Lightsource",
+        /// Arbitrary set of lights, the default.
+        LightGroup => "LightGroup",
+        /// Lights in a physical room, a light can only be in one room.
+        Room => "Room",
+        /// Lights used for entertainment streaming.
+        Entertainment => "Entertainment",
+        /// Lights in an area, a light can be in several zones.
+        Zone => "Zone",
+    }
+}
+
+impl GroupType {
+    /// Whether a group of this type has a class.
+    pub fn has_class(&self) -> bool {
+        matches!(
+            self,
+            GroupType::Room | GroupType::Zone | GroupType::Entertainment
+        )
+    }
+}
+
+string_enum! {
+    /// Category of a room, zone or entertainment group.
+    pub enum GroupClass {
+        LivingRoom => "Living room",
+        Kitchen => "Kitchen",
+        Dining => "Dining",
+        Bedroom => "Bedroom",
+        KidsBedroom => "Kids bedroom",
+        Bathroom => "Bathroom",
+        Nursery => "Nursery",
+        Recreation => "Recreation",
+        Office => "Office",
+        Gym => "Gym",
+        Hallway => "Hallway",
+        Toilet => "Toilet",
+        FrontDoor => "Front door",
+        Garage => "Garage",
+        Terrace => "Terrace",
+        Garden => "Garden",
+        Driveway => "Driveway",
+        Carport => "Carport",
+        Home => "Home",
+        Downstairs => "Downstairs",
+        Upstairs => "Upstairs",
+        TopFloor => "Top floor",
+        Attic => "Attic",
+        GuestRoom => "Guest room",
+        Staircase => "Staircase",
+        Lounge => "Lounge",
+        ManCave => "Man cave",
+        Computer => "Computer",
+        Studio => "Studio",
+        Music => "Music",
+        Tv => "TV",
+        Reading => "Reading",
+        Closet => "Closet",
+        Storage => "Storage",
+        LaundryRoom => "Laundry room",
+        Balcony => "Balcony",
+        Porch => "Porch",
+        Barbecue => "Barbecue",
+        Pool => "Pool",
+        Free => "Free",
+        Other => "Other",
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupState {
+    all_on: bool,
+    any_on: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Group<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<GroupId>,
+    name: String,
+    lights: Vec<LightId>,
+    #[serde(skip_serializing)]
+    sensors: Vec<SensorId>,
+    #[serde(rename = "type")]
+    ty: GroupType,
+    state: GroupState,
+    #[serde(skip_serializing)]
+    recycle: bool,
+    class: Option<GroupClass>,
+    #[serde(skip_serializing)]
+    action: GroupAction,
+}
+
+impl<'a, C: HTTPClient + Default + Debug> Group<'a, C> {
+    pub fn get_groups(http_client: &'a C) -> Res<BTreeMap<GroupId, Self>> {
+        let resp: String = http_client.get("groups")?;
+        let mut groups: BTreeMap<GroupId, Self> = serde_json::from_str(&resp)?;
+        for (id, group) in groups.iter_mut() {
+            group.id = Some(id.clone());
+            group.client = Some(http_client);
+        }
+        Ok(groups)
+    }
+
+    pub fn get_group(http_client: &'a C, id: &GroupId) -> Res<Self> {
+        let response = http_client.get(&format!("groups/{}", id))?;
+        let mut group: Self = serde_json::from_str(&response)?;
+        group.id = Some(id.clone());
+        group.client = Some(http_client);
+        Ok(group)
+    }
+
+    pub fn delete_group(http_client: &'a C, id: &GroupId) -> Res<()> {
+        let _response = http_client.delete(&format!("groups/{}", id))?;
+        Ok(())
+    }
+
+    /// Create a group and get it from the bridge.
+    ///
+    /// The bridge creates a `LightGroup` if no type is given. Class is only
+    /// valid for rooms, zones and entertainment groups, which only take `TV`
+    /// or `Free`; rooms without a class get the `Other` class.
+    pub fn create_group(
+        http_client: &'a C,
+        name: String,
+        lights: Vec<LightId>,
+        ty: Option<GroupType>,
+        class: Option<GroupClass>,
+    ) -> Res<Self> {
+        let mut body = serde_json::json!({
+            "name": name,
+            "lights": lights,
+        });
+        if let Some(class) = class {
+            match &ty {
+                Some(GroupType::Entertainment)
+                    if !matches!(class, GroupClass::Tv | GroupClass::Free) =>
+                {
+                    return Err("Entertainment groups only have the TV and Free classes".into())
+                }
+                Some(ty) if ty.has_class() => body["class"] = json!(class),
+                _ => {
+                    return Err(
+                        "class is only valid for Room, Zone and Entertainment groups".into(),
+                    )
+                }
+            }
+        }
+        if let Some(ty) = ty {
+            body["type"] = json!(ty);
+        }
+
+        capabilities::ensure_available(http_client, &[("groups", 1)])?;
+        let response = http_client.post("groups", body.to_string())?;
+        let id = created_id(&response)?;
+        Self::get_group(http_client, &id.parse()?)
+    }
+
+    /// Send the action attributes modified since the group was fetched and get the new group.
+    ///
+    /// Nothing is sent if no attribute was modified.
+    pub fn update_state(self) -> Res<Self> {
+        if self.action.is_changed() {
+            let state = self.action.changes()?.to_string();
+            let response = self
+                .client()
+                .put(&format!("groups/{}/action", self.id()), state)?;
+            response_success(&response)?;
+        }
+        Self::get_group(self.client.unwrap(), self.id())
+    }
+
+    /// Send the whole action, including unmodified attributes, and get the new group.
+    pub fn update_full_state(self) -> Res<Self> {
+        let state = serde_json::to_string(&self.action)?;
+        let response = self
+            .client()
+            .put(&format!("groups/{}/action", self.id()), state)?;
+        response_success(&response)?;
+        Self::get_group(self.client.unwrap(), self.id())
+    }
+
+    /// Send the writable attributes (name, lights and class) and get the updated group.
+    ///
+    /// The lights of a room are checked as by `set_lights`.
+    pub fn update(self) -> Res<Self> {
+        if self.ty == GroupType::Room {
+            self.check_rooms(&self.lights)?;
+        }
+        let mut attributes = json!({
+            "name": self.name,
+            "lights": self.lights,
+        });
+        if let Some(class) = &self.class {
+            attributes["class"] = json!(class);
+        }
+        self.put_attributes(attributes)?;
+        Self::get_group(self.client.unwrap(), self.id())
+    }
+
+    /// Recall a scene on the lights of the group.
+    ///
+    /// `transition` overrides the scene's transition time, in multiples of 100ms.
+    pub fn recall_scene(&self, scene_id: &SceneId, transition: Option<u16>) -> Res<()> {
+        let mut action = json!({ "scene": scene_id });
+        if let Some(time) = transition {
+            action["transitiontime"] = json!(time);
+        }
+        let response = self
+            .client()
+            .put(&format!("groups/{}/action", self.id()), action.to_string())?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Guess which scene of the group is active.
+    ///
+    /// Compares the group's scenes to the current state of the lights and
+    /// gets the closest scene whose lights are all within the tolerance.
+    pub fn active_scene(&self, tolerance: &Tolerance) -> Res<Option<Scene<'a, C>>> {
+        let client = self.client.unwrap();
+        let mut lights = Light::get_lights(client)?;
+        let mut closest: Option<(f32, Scene<'a, C>)> = None;
+        for (id, scene) in Scene::get_scenes(client)? {
+            if scene.group() != Some(self.id()) {
+                continue;
+            }
+            let scene = Scene::get_scene(client, &id)?;
+            let diffs = scene.diff_lights(&mut lights)?;
+            if diffs.is_empty() || !diffs.values().all(|diff| diff.is_within(tolerance)) {
+                continue;
+            }
+            let score = diffs.values().map(|diff| diff.score()).sum();
+            if closest.as_ref().is_none_or(|(closest, _)| score < *closest) {
+                closest = Some((score, scene));
+            }
+        }
+        Ok(closest.map(|(_, scene)| scene))
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.put_attributes(json!({ "name": name }))?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    /// Replace the lights of the group.
+    ///
+    /// A light can only be in one room, adding a light that is in another
+    /// room to a room is an error.
+    pub fn set_lights(&mut self, lights: &[LightId]) -> Res<&mut Self> {
+        if self.ty == GroupType::Room {
+            self.check_rooms(lights)?;
+        }
+        self.put_attributes(json!({ "lights": lights }))?;
+        self.lights = lights.to_vec();
+        Ok(self)
+    }
+
+    /// Add lights to the group, lights already in the group are ignored.
+    pub fn add_lights(&mut self, lights: &[LightId]) -> Res<&mut Self> {
+        let mut new_lights = self.lights.clone();
+        for id in lights {
+            if !new_lights.contains(id) {
+                new_lights.push(id.clone());
+            }
+        }
+        self.set_lights(&new_lights)
+    }
+
+    /// Remove lights from the group, lights not in the group are ignored.
+    pub fn remove_lights(&mut self, lights: &[LightId]) -> Res<&mut Self> {
+        let mut new_lights = self.lights.clone();
+        new_lights.retain(|id| !lights.contains(id));
+        self.set_lights(&new_lights)
+    }
+
+    fn check_rooms(&self, lights: &[LightId]) -> Res<()> {
+        let groups = Self::get_groups(self.client.unwrap())?;
+        let rooms = groups
+            .values()
+            .filter(|group| group.ty == GroupType::Room && group.id != self.id);
+        for room in rooms {
+            if let Some(light) = lights.iter().find(|light| room.lights.contains(light)) {
+                return Err(format!("light {} is already in room {}", light, room.name).into());
+            }
+        }
+        Ok(())
+    }
+
+    fn put_attributes(&self, attributes: serde_json::Value) -> Res<()> {
+        let response = self
+            .client()
+            .put(&format!("groups/{}", self.id()), attributes.to_string())?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    pub fn delete(self) -> Res<()> {
+        self.client().delete(&format!("groups/{}", self.id()))?;
+        Ok(())
+    }
+
+    pub fn id(&self) -> &GroupId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn action(&mut self) -> &mut GroupAction {
+        &mut self.action
+    }
+
+    pub fn ty(&self) -> &GroupType {
+        &self.ty
+    }
+
+    pub fn class(&self) -> Option<&GroupClass> {
+        self.class.as_ref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn lights(&self) -> &[LightId] {
+        &self.lights
+    }
+
+    pub fn sensors(&self) -> &[SensorId] {
+        &self.sensors
+    }
+
+    pub fn recycle(&self) -> bool {
+        self.recycle
+    }
+}
+
+impl<'a, C: HTTPClient + Default + Debug> Resource<'a, C> for Group<'a, C> {
+    type Id = GroupId;
+
+    const PATH: &'static str = "groups";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<GroupId, Self>> {
+        Self::get_groups(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &GroupId) -> Res<Self> {
+        Self::get_group(http_client, id)
+    }
+
+    fn id(&self) -> GroupId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_groups {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    #[test]
+    fn get_group_ok() {
+        let response = String::from(
+            r#"
+              {
+                "name": "Kitchen",
+                "lights": [
+                  "1"
+                ],
+                "sensors": [],
+                "type": "Room",
+                "state": {
+                  "all_on": false,
+                  "any_on": false
+                },
+                "recycle": false,
+                "class": "Kitchen",
+                "action": {
+                  "on": false,
+                  "bri": 144,
+                  "alert": "none"
+                }
+              } "#,
+        );
+
+        let http_client_mock = HTTPClientMock {
+            body: None,
+            return_string: Some(response),
+            error: None,
+            ..Default::default()
+        };
+        let group = Group::get_group(&http_client_mock, &1.into());
+        assert!(group.is_ok());
+    }
+
+    #[test]
+    fn get_group_err() {
+        let response = String::from("not expected response");
+        let http_client_mock = HTTPClientMock {
+            body: None,
+            return_string: Some(response),
+            error: None,
+            ..Default::default()
+        };
+        let group = Group::get_group(&http_client_mock, &1.into());
+        assert!(group.is_err());
+    }
+
+    const GROUPS: &str = r#"
+        {
+            "1": {
+                "name": "Kitchen",
+                "lights": ["1", "2"],
+                "sensors": [],
+                "type": "Room",
+                "state": {"all_on": false, "any_on": false},
+                "recycle": false,
+                "class": "Kitchen",
+                "action": {"on": false}
+            },
+            "2": {
+                "name": "Living",
+                "lights": ["3"],
+                "sensors": [],
+                "type": "Room",
+                "state": {"all_on": false, "any_on": false},
+                "recycle": false,
+                "class": "Living room",
+                "action": {"on": false}
+            },
+            "3": {
+                "name": "Downstairs",
+                "lights": ["1", "3"],
+                "sensors": [],
+                "type": "Zone",
+                "state": {"all_on": false, "any_on": false},
+                "recycle": false,
+                "class": "Downstairs",
+                "action": {"on": false}
+            }
+        }"#;
+
+    fn groups_client() -> HTTPClientMock {
+        let groups: serde_json::Value = serde_json::from_str(GROUPS).unwrap();
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{}}]"#.to_owned()),
+            ..Default::default()
+        };
+        client
+            .responses
+            .insert("groups".to_owned(), GROUPS.to_owned());
+        for (id, group) in groups.as_object().unwrap() {
+            client
+                .responses
+                .insert(format!("groups/{}", id), group.to_string());
+        }
+        client
+    }
+
+    fn last_put(client: &HTTPClientMock) -> (String, serde_json::Value) {
+        let requests = client.requests.borrow();
+        let (_, call, body) = requests
+            .iter()
+            .rfind(|(method, _, _)| method == "PUT")
+            .unwrap();
+        (call.clone(), serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn group_membership() {
+        let client = groups_client();
+        let mut zone = Group::get_group(&client, &3.into()).unwrap();
+
+        zone.add_lights(&[2.into(), 3.into()]).unwrap();
+        assert_eq!(zone.lights(), ["1", "3", "2"]);
+        assert_eq!(
+            last_put(&client),
+            ("groups/3".to_owned(), json!({"lights": ["1", "3", "2"]}))
+        );
+
+        zone.remove_lights(&[1.into(), 4.into()]).unwrap();
+        assert_eq!(zone.lights(), ["3", "2"]);
+
+        zone.rename("Ground floor").unwrap();
+        assert_eq!(zone.name(), "Ground floor");
+        assert_eq!(
+            last_put(&client),
+            ("groups/3".to_owned(), json!({"name": "Ground floor"}))
+        );
+    }
+
+    #[test]
+    fn group_membership_room() {
+        let client = groups_client();
+        let mut kitchen = Group::get_group(&client, &1.into()).unwrap();
+
+        assert!(kitchen.add_lights(&[3.into()]).is_err());
+        assert_eq!(kitchen.lights(), ["1", "2"]);
+
+        kitchen.set_lights(&[2.into(), 4.into()]).unwrap();
+        assert_eq!(kitchen.lights(), ["2", "4"]);
+    }
+
+    #[test]
+    fn group_recall_scene() {
+        let client = groups_client();
+        let group = Group::get_group(&client, &2.into()).unwrap();
+        group
+            .recall_scene(&"AbCdEfGh".parse().unwrap(), Some(10))
+            .unwrap();
+        assert_eq!(
+            last_put(&client),
+            (
+                "groups/2/action".to_owned(),
+                json!({"scene": "AbCdEfGh", "transitiontime": 10})
+            )
+        );
+    }
+
+    #[test]
+    fn group_update_state_error() {
+        let mut client = groups_client();
+        client.body = Some(
+            r#"[{"error":{"type":7,"address":"/groups/2/action/bri","description":"invalid value, 300, for parameter, bri"}}]"#
+                .to_owned(),
+        );
+        let mut group = Group::get_group(&client, &2.into()).unwrap();
+        group.action().set_on(true);
+        assert!(group.update_state().is_err());
+        let group = Group::get_group(&client, &2.into()).unwrap();
+        assert!(group.update_full_state().is_err());
+    }
+
+    #[test]
+    fn group_update_writable() {
+        let client = groups_client();
+        let group = Group::get_group(&client, &1.into()).unwrap();
+        group.update().unwrap();
+        assert_eq!(
+            last_put(&client),
+            (
+                "groups/1".to_owned(),
+                json!({"name": "Kitchen", "lights": ["1", "2"], "class": "Kitchen"})
+            )
+        );
+
+        let mut client = groups_client();
+        let kitchen = client.responses["groups/1"].replace(r#"["1","2"]"#, r#"["1","3"]"#);
+        client.responses.insert("groups/1".to_owned(), kitchen);
+        let group = Group::get_group(&client, &1.into()).unwrap();
+        assert_eq!(group.lights(), ["1", "3"]);
+        assert!(group.update().is_err());
+        assert!(client
+            .requests
+            .borrow()
+            .iter()
+            .all(|(method, _, _)| method == "GET"));
+
+        let mut client = groups_client();
+        client.body = Some(
+            r#"[{"error":{"type":7,"address":"/groups/2/name","description":"invalid value"}}]"#
+                .to_owned(),
+        );
+        let group = Group::get_group(&client, &2.into()).unwrap();
+        assert!(group.update().is_err());
+    }
+
+    #[test]
+    fn create_group_ok() {
+        let response = String::from(
+            r#"
+              {
+                "name": "Living",
+                "lights": ["1", "2"],
+                "sensors": [],
+                "type": "Room",
+                "state": {"all_on": false, "any_on": false},
+                "recycle": false,
+                "class": "Living room",
+                "action": {"on": false}
+              } "#,
+        );
+        let http_client_mock = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"7"}}]"#.to_owned()),
+            return_string: Some(response),
+            ..Default::default()
+        }
+        .with_capabilities();
+
+        let group = Group::create_group(
+            &http_client_mock,
+            "Living".to_owned(),
+            vec![1.into(), 2.into()],
+            Some(GroupType::Room),
+            Some(GroupClass::LivingRoom),
+        )
+        .unwrap();
+        assert_eq!(group.id(), "7");
+        assert_eq!(group.ty(), &GroupType::Room);
+        assert_eq!(group.class(), Some(&GroupClass::LivingRoom));
+
+        let requests = http_client_mock.requests.borrow();
+        assert_eq!(requests[0].1, "capabilities");
+        let (_, call, body) = &requests[1];
+        assert_eq!(call, "groups");
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(
+            body,
+            json!({"name": "Living", "lights": ["1", "2"], "type": "Room", "class": "Living room"})
+        );
+        assert_eq!(requests[2].1, "groups/7");
+    }
+
+    #[test]
+    fn create_group_err() {
+        let http_client_mock = HTTPClientMock {
+            body: Some(
+                r#"[{"error":{"type":7,"address":"/groups/lights","description":"invalid value, 99, for parameter, lights"}}]"#
+                    .to_owned(),
+            ),
+            ..Default::default()
+        }
+        .with_capabilities();
+        let group = Group::create_group(
+            &http_client_mock,
+            "Living".to_owned(),
+            vec![99.into()],
+            None,
+            None,
+        );
+        assert!(group.is_err());
+
+        let group = Group::create_group(
+            &http_client_mock,
+            "Living".to_owned(),
+            vec![1.into()],
+            Some(GroupType::LightGroup),
+            Some(GroupClass::LivingRoom),
+        );
+        assert!(group.is_err());
+
+        let group = Group::create_group(
+            &http_client_mock,
+            "Living".to_owned(),
+            vec![1.into()],
+            Some(GroupType::Entertainment),
+            Some(GroupClass::LivingRoom),
+        );
+        assert!(group.is_err());
+        assert_eq!(http_client_mock.requests.borrow().len(), 2);
+    }
+}
@@ -0,0 +1,230 @@
+//! Models of the Philips Hue API, without any I/O.
+//!
+//! Resources call the bridge through the `HTTPClient` trait, implemented by
+//! the `hue` crate over HTTP. Waiting for the bridge, which blocks, is in the
+//! `hue::wait` module.
+
+use std::error::Error;
+use std::fmt;
+#[macro_use]
+mod macros;
+pub mod capabilities;
+pub mod colors;
+pub mod config;
+pub mod firmware;
+pub mod groups;
+pub mod lights;
+pub mod resource;
+pub mod resourcelinks;
+pub mod rules;
+pub mod scenes;
+pub mod schedules;
+pub mod sensors;
+pub mod timestamp;
+pub mod timezones;
+
+extern crate chrono;
+extern crate chrono_tz;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+#[macro_use]
+extern crate derive_builder;
+
+pub type Res<T> = Result<T, Box<dyn Error>>;
+
+/// Error reported by the bridge for a request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HueError {
+    #[serde(rename = "type")]
+    ty: u16,
+    address: String,
+    description: String,
+}
+
+impl HueError {
+    pub fn ty(&self) -> u16 {
+        self.ty
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl fmt::Display for HueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.description, self.address)
+    }
+}
+
+impl Error for HueError {}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum HueResponse {
+    Success(serde_json::Value),
+    Error(HueError),
+}
+
+/// Get the success values of a bridge response, or the first error reported.
+///
+/// Responses look like `[{"success":{"/lights/1/name":"Hall"}},{"error":{...}}]`.
+pub fn response_success(response: &str) -> Res<Vec<serde_json::Value>> {
+    let items: Vec<HueResponse> = serde_json::from_str(response)?;
+    items
+        .into_iter()
+        .map(|item| match item {
+            HueResponse::Success(value) => Ok(value),
+            HueResponse::Error(e) => Err(e.into()),
+        })
+        .collect()
+}
+
+/// Get the ID of a resource created on the bridge, e.g. `[{"success":{"id":"7"}}]`.
+pub fn created_id(response: &str) -> Res<String> {
+    let success = response_success(response)?;
+    success
+        .iter()
+        .find_map(|value| value["id"].as_str())
+        .map(|id| id.to_owned())
+        .ok_or_else(|| format!("no ID in response: {}", response).into())
+}
+
+pub trait HTTPClient {
+    fn get(&self, call: &str) -> Res<String>;
+    fn post(&self, call: &str, body: String) -> Res<String>;
+    fn put(&self, call: &str, body: String) -> Res<String>;
+    fn delete(&self, call: &str) -> Res<String>;
+    /// Username the calls are made with, as in `/api/<username>/lights`, if known.
+    fn username(&self) -> Option<&str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests_response {
+
+    use super::*;
+
+    #[test]
+    fn created_id_ok() {
+        assert_eq!(created_id(r#"[{"success":{"id":"7"}}]"#).unwrap(), "7");
+    }
+
+    #[test]
+    fn created_id_err() {
+        let response = r#"[{"error":{"type":7,"address":"/groups/lights","description":"invalid value, 99, for parameter, lights"}}]"#;
+        let e = created_id(response).unwrap_err();
+        let e = e.downcast_ref::<HueError>().unwrap();
+        assert_eq!(e.ty(), 7);
+        assert_eq!(e.address(), "/groups/lights");
+
+        assert!(created_id(r#"[{"success":{}}]"#).is_err());
+        assert!(created_id("not json").is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_common {
+
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    const CAPABILITIES: &str = r#"
+        {
+            "lights": {"available": 50, "total": 63},
+            "sensors": {
+                "available": 240, "total": 250,
+                "clip": {"available": 240, "total": 250},
+                "zll": {"available": 60, "total": 64},
+                "zgp": {"available": 60, "total": 64}
+            },
+            "groups": {"available": 60, "total": 64},
+            "scenes": {
+                "available": 172, "total": 200,
+                "lightstates": {"available": 2000, "total": 2048}
+            },
+            "schedules": {"available": 95, "total": 100},
+            "rules": {
+                "available": 233, "total": 250,
+                "conditions": {"available": 1451, "total": 1500},
+                "actions": {"available": 964, "total": 1000}
+            },
+            "resourcelinks": {"available": 59, "total": 64}
+        }"#;
+
+    #[derive(Default, Debug)]
+    pub struct HTTPClientMock {
+        pub body: Option<String>,
+        pub return_string: Option<String>,
+        pub error: Option<String>,
+        /// Responses to GET calls by path, `return_string` is used for other paths.
+        pub responses: BTreeMap<String, String>,
+        /// Responses to PUT calls by path, `body` is used for other paths.
+        pub put_responses: BTreeMap<String, String>,
+        /// Calls made as (method, path, body).
+        pub requests: RefCell<Vec<(String, String, String)>>,
+        /// Username the calls are made with, unknown by default.
+        pub username: Option<String>,
+    }
+
+    impl HTTPClientMock {
+        /// Answer `capabilities` as a bridge with room for everything.
+        pub fn with_capabilities(mut self) -> Self {
+            self.responses
+                .insert("capabilities".to_owned(), CAPABILITIES.to_owned());
+            self
+        }
+
+        fn record(&self, method: &str, call: &str, body: &str) {
+            self.requests
+                .borrow_mut()
+                .push((method.to_owned(), call.to_owned(), body.to_owned()));
+        }
+    }
+
+    impl HTTPClient for HTTPClientMock {
+        fn get(&self, call: &str) -> Res<String> {
+            self.record("GET", call, "");
+            if let Some(s) = self.responses.get(call) {
+                Ok(s.to_owned())
+            } else if let Some(s) = self.return_string.as_ref() {
+                Ok(s.to_owned())
+            } else {
+                let e_str = self.error.as_ref().unwrap().clone();
+                Err(e_str.into())
+            }
+        }
+
+        fn post(&self, call: &str, body: String) -> Res<String> {
+            self.record("POST", call, &body);
+            Ok(self.body.clone().unwrap_or_default())
+        }
+
+        fn put(&self, call: &str, body: String) -> Res<String> {
+            self.record("PUT", call, &body);
+            match self.put_responses.get(call) {
+                Some(response) => Ok(response.to_owned()),
+                None => Ok(self.body.clone().unwrap_or_default()),
+            }
+        }
+
+        fn delete(&self, call: &str) -> Res<String> {
+            self.record("DELETE", call, "");
+            Ok(self.body.clone().unwrap_or_default())
+        }
+
+        fn username(&self) -> Option<&str> {
+            self.username.as_deref()
+        }
+    }
+}
@@ -0,0 +1,1177 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::colors::{self, Gamut, NamedColor};
+use super::firmware::UpdateState;
+use super::resource::Resource;
+use super::*;
+
+string_id! {
+    /// ID of a light, e.g. `3`.
+    pub struct LightId;
+}
+
+string_enum! {
+    /// Temporary change of the light, used to identify it.
+    pub enum Alert {
+        None => "none",
+        /// One breathe cycle.
+        Select => "select",
+        /// Breathe cycles for 15 seconds.
+        LSelect => "lselect",
+    }
+}
+
+string_enum! {
+    /// Dynamic effect of the light.
+    pub enum Effect {
+        None => "none",
+        /// Cycle through all hues with the current brightness and saturation.
+        ColorLoop => "colorloop",
+    }
+}
+
+string_enum! {
+    /// Color settings the light is currently in.
+    pub enum ColorMode {
+        /// Hue and saturation.
+        Hs => "hs",
+        Xy => "xy",
+        /// Color temperature.
+        Ct => "ct",
+    }
+}
+
+string_enum! {
+    /// Whether the light is controlled through the API or streamed to.
+    pub enum LightMode {
+        HomeAutomation => "homeautomation",
+        Streaming => "streaming",
+    }
+}
+
+#[derive(Builder, Serialize, Deserialize, Default, Debug, Clone)]
+#[builder(default)]
+#[builder(setter(into))]
+pub struct LightState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bri: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hue: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sat: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alert: Option<Alert>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effect: Option<Effect>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    xy: Option<[f32; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ct: Option<u16>,
+    #[serde(skip_serializing)]
+    colormode: Option<ColorMode>,
+    #[serde(skip_serializing)]
+    mode: Option<LightMode>,
+    #[serde(skip_serializing)]
+    reachable: Option<bool>,
+    // PUT, and stored in scene light states
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transitiontime: Option<u16>, // 4 = 400 msec
+    // attributes modified since the state was fetched
+    #[serde(skip)]
+    #[builder(setter(skip))]
+    changed: BTreeSet<&'static str>,
+}
+
+impl LightState {
+    pub fn on(&self) -> bool {
+        self.on.unwrap()
+    }
+    pub fn set_on(&mut self, power: bool) -> &mut Self {
+        self.on = Some(power);
+        self.changed.insert("on");
+        self
+    }
+    pub fn bri(&self) -> u8 {
+        self.bri.unwrap()
+    }
+    pub fn set_bri(&mut self, bri: u8) -> &mut Self {
+        self.bri = Some(bri);
+        self.changed.insert("bri");
+        self
+    }
+    pub fn hue(&self) -> u16 {
+        self.hue.unwrap_or_default()
+    }
+    pub fn set_hue(&mut self, hue: u16) -> &mut Self {
+        if self.hue.is_some() {
+            self.hue = Some(hue);
+            self.changed.insert("hue");
+        }
+        self
+    }
+    pub fn sat(&self) -> u8 {
+        self.sat.unwrap_or_default()
+    }
+    pub fn set_sat(&mut self, sat: u8) -> &mut Self {
+        if self.sat.is_some() {
+            self.sat = Some(sat);
+            self.changed.insert("sat");
+        }
+        self
+    }
+    pub fn ct(&self) -> u16 {
+        self.ct.unwrap_or_default()
+    }
+    pub fn set_ct(&mut self, ct: u16) -> &mut Self {
+        if self.ct.is_some() {
+            self.ct = Some(ct);
+            self.changed.insert("ct");
+        }
+        self
+    }
+    pub fn xy(&self) -> [f32; 2] {
+        self.xy.unwrap_or([0.0, 0.0])
+    }
+    pub fn set_xy(&mut self, xy: [f32; 2]) -> &mut Self {
+        if self.xy.is_some() {
+            self.xy = Some(xy);
+            self.changed.insert("xy");
+        }
+        self
+    }
+    pub fn alert(&self) -> Option<&Alert> {
+        self.alert.as_ref()
+    }
+    pub fn set_alert(&mut self, alert: Alert) -> &mut Self {
+        self.alert = Some(alert);
+        self.changed.insert("alert");
+        self
+    }
+    pub fn effect(&self) -> Option<&Effect> {
+        self.effect.as_ref()
+    }
+    pub fn set_effect(&mut self, effect: Effect) -> &mut Self {
+        self.effect = Some(effect);
+        self.changed.insert("effect");
+        self
+    }
+    pub fn colormode(&self) -> Option<&ColorMode> {
+        self.colormode.as_ref()
+    }
+    pub fn mode(&self) -> Option<&LightMode> {
+        self.mode.as_ref()
+    }
+    /// Set the color by its name, see `colors::named_color`.
+    ///
+    /// Colors are clamped to the gamut if one is given, whites to the color
+    /// temperature range if one is given, otherwise to the range of Hue lights.
+    pub fn set_named_color(
+        &mut self,
+        name: &str,
+        gamut: Option<&Gamut>,
+        ct_range: Option<&CtRange>,
+    ) -> Res<&mut Self> {
+        match colors::named_color(name) {
+            Some(NamedColor::Xy(xy)) => {
+                self.xy = Some(gamut.map_or(xy, |gamut| gamut.clamp(xy)));
+                self.ct = None;
+                self.colormode = Some(ColorMode::Xy);
+                self.changed.insert("xy");
+            }
+            Some(NamedColor::Ct(ct)) => {
+                self.ct = Some(match ct_range {
+                    Some(range) => ct.clamp(range.min, range.max),
+                    None => ct.clamp(colors::CT_MIN, colors::CT_MAX),
+                });
+                self.xy = None;
+                self.colormode = Some(ColorMode::Ct);
+                self.changed.insert("ct");
+            }
+            None => return Err(format!("unknown color: {}", name).into()),
+        }
+        self.hue = None;
+        self.sat = None;
+        Ok(self)
+    }
+    /// Check if any attribute was set since the state was fetched.
+    pub fn is_changed(&self) -> bool {
+        !self.changed.is_empty()
+    }
+    /// Get only the attributes set since the state was fetched, as sent to the bridge.
+    pub fn changes(&self) -> Res<serde_json::Value> {
+        let mut state = serde_json::to_value(self)?;
+        if let Some(attributes) = state.as_object_mut() {
+            attributes.retain(|name, _| self.changed.contains(name.as_str()));
+        }
+        Ok(state)
+    }
+    /// Forget about modified attributes, e.g. after they were sent to the bridge.
+    pub fn clear_changes(&mut self) -> &mut Self {
+        self.changed.clear();
+        self
+    }
+    pub fn transitiontime(&self) -> Option<u16> {
+        self.transitiontime
+    }
+    pub fn set_transitiontime(&mut self, time: u16) -> &mut Self {
+        self.transitiontime = Some(time);
+        self.changed.insert("transitiontime");
+        self
+    }
+    /// Compare the state to the expected one, e.g. a light state of a scene.
+    ///
+    /// Colors are compared by color temperature when both states are in `ct`
+    /// color mode, otherwise by distance in the xy color space.
+    pub fn diff(&self, expected: &LightState) -> StateDiff {
+        let mut diff = StateDiff::default();
+        let (on, expected_on) = (self.on.unwrap_or(false), expected.on.unwrap_or(false));
+        if on != expected_on {
+            diff.on = Some((expected_on, on));
+            return diff;
+        }
+        if !on {
+            return diff;
+        }
+
+        if let (Some(bri), Some(expected_bri)) = (self.bri, expected.bri) {
+            diff.bri = i16::from(bri) - i16::from(expected_bri);
+        }
+        let in_ct_mode = self.colormode == Some(ColorMode::Ct) || self.xy.is_none();
+        match (expected.xy, expected.ct) {
+            (Some(expected_xy), _) => {
+                diff.xy = self.xy.map(|xy| colors::xy_distance(xy, expected_xy));
+            }
+            (None, Some(expected_ct)) if in_ct_mode => {
+                diff.ct = self.ct.map(|ct| i32::from(ct) - i32::from(expected_ct));
+            }
+            (None, Some(expected_ct)) => {
+                let expected_xy = colors::ct_to_xy(expected_ct);
+                diff.xy = self.xy.map(|xy| colors::xy_distance(xy, expected_xy));
+            }
+            (None, None) => {}
+        }
+        diff
+    }
+}
+
+/// Allowed difference of a light from its expected state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    bri: u8,
+    xy: f32,
+    ct: u16,
+}
+
+impl Tolerance {
+    /// Create a tolerance of brightness steps, xy distance and mireds.
+    pub fn new(bri: u8, xy: f32, ct: u16) -> Self {
+        Tolerance { bri, xy, ct }
+    }
+}
+
+impl Default for Tolerance {
+    /// Covers the rounding of values reported by the lights.
+    fn default() -> Self {
+        Tolerance {
+            bri: 3,
+            xy: 0.01,
+            ct: 5,
+        }
+    }
+}
+
+/// Difference of a light state from its expected state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StateDiff {
+    on: Option<(bool, bool)>,
+    bri: i16,
+    xy: Option<f32>,
+    ct: Option<i32>,
+}
+
+impl StateDiff {
+    /// Expected and actual power state, if they differ.
+    pub fn on(&self) -> Option<(bool, bool)> {
+        self.on
+    }
+    /// Actual minus expected brightness.
+    pub fn bri(&self) -> i16 {
+        self.bri
+    }
+    /// Distance of the colors in the xy color space.
+    pub fn xy(&self) -> Option<f32> {
+        self.xy
+    }
+    /// Actual minus expected color temperature in mireds.
+    pub fn ct(&self) -> Option<i32> {
+        self.ct
+    }
+    pub fn is_within(&self, tolerance: &Tolerance) -> bool {
+        self.on.is_none()
+            && self.bri.unsigned_abs() <= u16::from(tolerance.bri)
+            && self.xy.is_none_or(|xy| xy <= tolerance.xy)
+            && self
+                .ct
+                .is_none_or(|ct| ct.unsigned_abs() <= u32::from(tolerance.ct))
+    }
+    /// Single number to rank differences, 0 for equal states and at least 1
+    /// for a light that is on instead of off or the other way around.
+    pub fn score(&self) -> f32 {
+        if self.on.is_some() {
+            return 1.0;
+        }
+        let bri = f32::from(self.bri.unsigned_abs()) / 254.0;
+        let xy = self.xy.unwrap_or(0.0);
+        let ct = self.ct.map_or(0.0, |ct| ct.unsigned_abs() as f32)
+            / f32::from(colors::CT_MAX - colors::CT_MIN);
+        bri + xy + ct
+    }
+}
+
+#[cfg(test)]
+mod tests_light_state {
+
+    use super::*;
+
+    #[test]
+    fn state_on_off() {
+        let mut state = LightStateBuilder::default().on(true).build().unwrap();
+        assert!(state.on());
+        state.set_on(false);
+        assert!(!state.on());
+    }
+
+    #[test]
+    fn state_bri() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        state.set_bri(100);
+        assert_eq!(state.bri(), 100);
+    }
+
+    #[test]
+    fn state_hue() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        assert_eq!(state.hue(), 0);
+        state.set_hue(100);
+        assert_eq!(state.hue(), 0);
+
+        let mut state = LightStateBuilder::default().hue(Some(10)).build().unwrap();
+        state.set_hue(100);
+        assert_eq!(state.hue(), 100);
+    }
+
+    #[test]
+    fn state_sat() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        assert_eq!(state.sat(), 0);
+        state.set_sat(100);
+        assert_eq!(state.sat(), 0);
+
+        let mut state = LightStateBuilder::default().sat(Some(10)).build().unwrap();
+        state.set_sat(100);
+        assert_eq!(state.sat(), 100);
+    }
+
+    #[test]
+    fn state_ct() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        assert_eq!(state.ct(), 0);
+        state.set_ct(100);
+        assert_eq!(state.ct(), 0);
+
+        let mut state = LightStateBuilder::default().ct(Some(10)).build().unwrap();
+        state.set_ct(100);
+        assert_eq!(state.ct(), 100);
+    }
+
+    #[test]
+    fn state_xy() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        assert_eq!(state.xy(), [0.0, 0.0]);
+        state.set_xy([10.0, 10.0]);
+        assert_eq!(state.xy(), [0.0, 0.0]);
+
+        let mut state = LightStateBuilder::default()
+            .xy(Some([0.0, 0.0]))
+            .build()
+            .unwrap();
+        state.set_xy([10.0, 10.0]);
+        assert_eq!(state.xy(), [10.0, 10.0]);
+    }
+
+    #[test]
+    fn state_alert() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        assert_eq!(state.alert(), None);
+        state.set_alert(Alert::Select);
+        assert_eq!(state.alert(), Some(&Alert::Select));
+        assert!("something".parse::<Alert>().is_err());
+        assert_eq!(Alert::from("lselect"), Alert::LSelect);
+    }
+
+    #[test]
+    fn state_effect() {
+        let mut state = LightStateBuilder::default().build().unwrap();
+        assert_eq!(state.effect(), None);
+        state.set_effect(Effect::ColorLoop);
+        assert_eq!(state.effect(), Some(&Effect::ColorLoop));
+        assert!("something".parse::<Effect>().is_err());
+    }
+
+    #[test]
+    fn state_named_color() {
+        let mut state = LightStateBuilder::default()
+            .ct(Some(300))
+            .hue(Some(100))
+            .build()
+            .unwrap();
+        state
+            .set_named_color("red", Some(&colors::GAMUT_B), None)
+            .unwrap();
+        assert!(colors::GAMUT_B.contains(state.xy()));
+        assert_eq!(state.ct, None);
+        assert_eq!(state.hue, None);
+        assert_eq!(state.colormode(), Some(&ColorMode::Xy));
+
+        state.set_named_color("concentrate", None, None).unwrap();
+        assert_eq!(state.ct(), 233);
+        assert_eq!(state.xy, None);
+        state
+            .set_named_color("relax", None, Some(&CtRange::new(200, 400)))
+            .unwrap();
+        assert_eq!(state.ct(), 400);
+
+        assert!(state.set_named_color("ultraviolet", None, None).is_err());
+    }
+
+    #[test]
+    fn state_changes() {
+        let mut state: LightState =
+            serde_json::from_str(r#"{"on": false, "bri": 100, "ct": 300, "alert": "none"}"#)
+                .unwrap();
+        assert!(!state.is_changed());
+        assert_eq!(state.changes().unwrap(), json!({}));
+
+        state.set_on(true).set_ct(250).set_transitiontime(4);
+        assert!(state.is_changed());
+        assert_eq!(
+            state.changes().unwrap(),
+            json!({"on": true, "ct": 250, "transitiontime": 4})
+        );
+
+        state.clear_changes();
+        assert!(!state.is_changed());
+    }
+
+    #[test]
+    fn state_diff() {
+        let scene: LightState =
+            serde_json::from_str(r#"{"on": true, "bri": 200, "ct": 366}"#).unwrap();
+
+        let actual: LightState = serde_json::from_str(
+            r#"{"on": true, "bri": 202, "ct": 369, "xy": [0.45, 0.41], "colormode": "ct"}"#,
+        )
+        .unwrap();
+        let diff = actual.diff(&scene);
+        assert_eq!(diff.bri(), 2);
+        assert_eq!(diff.ct(), Some(3));
+        assert!(diff.is_within(&Tolerance::default()));
+
+        let actual: LightState = serde_json::from_str(
+            r#"{"on": true, "bri": 200, "xy": [0.2, 0.3], "colormode": "xy"}"#,
+        )
+        .unwrap();
+        let diff = actual.diff(&scene);
+        assert!(diff.xy().unwrap() > 0.1);
+        assert!(!diff.is_within(&Tolerance::default()));
+
+        let actual: LightState = serde_json::from_str(r#"{"on": false, "bri": 200}"#).unwrap();
+        let diff = actual.diff(&scene);
+        assert_eq!(diff.on(), Some((true, false)));
+        assert_eq!(diff.score(), 1.0);
+
+        let off: LightState = serde_json::from_str(r#"{"on": false}"#).unwrap();
+        assert_eq!(actual.diff(&off), StateDiff::default());
+    }
+
+    #[test]
+    fn state_enums_serde() {
+        let state: LightState = serde_json::from_str(
+            r#"{"alert": "breathe", "effect": "colorloop", "colormode": "ct", "mode": "streaming"}"#,
+        )
+        .unwrap();
+        assert_eq!(state.alert(), Some(&Alert::Unknown("breathe".to_owned())));
+        assert_eq!(state.effect(), Some(&Effect::ColorLoop));
+        assert_eq!(state.colormode(), Some(&ColorMode::Ct));
+        assert_eq!(state.mode(), Some(&LightMode::Streaming));
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, r#"{"alert":"breathe","effect":"colorloop"}"#);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LightSWUpdate {
+    state: UpdateState,
+    #[serde(with = "crate::timestamp", default)]
+    lastinstall: Option<NaiveDateTime>,
+}
+
+impl LightSWUpdate {
+    pub fn state(&self) -> &UpdateState {
+        &self.state
+    }
+
+    pub fn lastinstall(&self) -> Option<NaiveDateTime> {
+        self.lastinstall
+    }
+}
+
+string_enum! {
+    /// Behavior of a light after it is powered on.
+    pub enum StartupMode {
+        /// Bright warm white, the default of all Hue lights.
+        Safety => "safety",
+        /// Recover the state from before a power failure, otherwise safety.
+        Powerfail => "powerfail",
+        /// Recover the last state the light was on in.
+        LastOnState => "lastonstate",
+        /// Use the configured custom settings.
+        Custom => "custom",
+    }
+}
+
+/// Color and brightness used by the `custom` startup mode.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct StartupSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bri: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    xy: Option<[f32; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ct: Option<u16>,
+}
+
+impl StartupSettings {
+    pub fn bri(&self) -> Option<u8> {
+        self.bri
+    }
+    pub fn xy(&self) -> Option<[f32; 2]> {
+        self.xy
+    }
+    pub fn ct(&self) -> Option<u16> {
+        self.ct
+    }
+}
+
+impl From<&LightState> for StartupSettings {
+    /// Take brightness and color of the state. Color temperature wins over xy
+    /// when the state is in `ct` color mode.
+    fn from(state: &LightState) -> Self {
+        let ct_mode = match &state.colormode {
+            Some(mode) => *mode == ColorMode::Ct,
+            None => state.xy.is_none(),
+        };
+        StartupSettings {
+            bri: state.bri,
+            xy: if ct_mode { None } else { state.xy },
+            ct: if ct_mode { state.ct } else { None },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct LightStartup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<StartupMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    configured: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    customsettings: Option<StartupSettings>,
+}
+
+impl LightStartup {
+    pub fn mode(&self) -> Option<&StartupMode> {
+        self.mode.as_ref()
+    }
+    /// Whether the light has applied the configured startup mode.
+    pub fn configured(&self) -> bool {
+        self.configured.unwrap_or(false)
+    }
+    pub fn customsettings(&self) -> Option<&StartupSettings> {
+        self.customsettings.as_ref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct LightConfig {
+    archetype: Option<String>,
+    function: Option<String>,
+    direction: Option<String>,
+    startup: Option<LightStartup>,
+}
+
+/// Color temperature range of a light in mired.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct CtRange {
+    min: u16,
+    max: u16,
+}
+
+impl CtRange {
+    pub fn new(min: u16, max: u16) -> Self {
+        CtRange { min, max }
+    }
+    pub fn min(&self) -> u16 {
+        self.min
+    }
+    pub fn max(&self) -> u16 {
+        self.max
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct LightControl {
+    mindimlevel: Option<u32>,
+    maxlumen: Option<u32>,
+    colorgamuttype: Option<String>,
+    colorgamut: Option<[[f32; 2]; 3]>,
+    ct: Option<CtRange>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct LightCapabilities {
+    certified: Option<bool>,
+    #[serde(default)]
+    control: LightControl,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Light<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<LightId>,
+    state: LightState,
+    swupdate: LightSWUpdate,
+    #[serde(rename = "type")]
+    ty: String,
+    name: String,
+    modelid: String,
+    manufacturername: String,
+    productname: String,
+    uniqueid: String,
+    swversion: String,
+    #[serde(default)]
+    capabilities: LightCapabilities,
+    #[serde(default)]
+    config: LightConfig,
+}
+
+/// API for operations on the lights.
+impl<'a, C: HTTPClient + Default> Light<'a, C> {
+    /// Get all registered lights.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hue_core::lights::*;
+    /// use hue_core::*;
+    ///
+    /// fn example<C: HTTPClient + Default>(client: &C) {
+    ///     let lights = Light::get_lights(client).unwrap();
+    ///
+    ///     assert!(!lights.is_empty());
+    /// }
+    /// ```
+    pub fn get_lights(http_client: &'a C) -> Res<BTreeMap<LightId, Self>> {
+        let resp = http_client.get("lights")?;
+        let mut lights: BTreeMap<LightId, Self> = serde_json::from_str(&resp)?;
+        for (id, light) in lights.iter_mut() {
+            light.id = Some(id.clone());
+            light.client = Some(http_client);
+        }
+        Ok(lights)
+    }
+
+    pub fn get_light(http_client: &'a C, id: &LightId) -> Res<Self> {
+        let response = http_client.get(&format!("lights/{}", id))?;
+        let mut light: Self = serde_json::from_str(&response)?;
+        light.id = Some(id.clone());
+        light.client = Some(http_client);
+        Ok(light)
+    }
+
+    /// Send the state attributes modified since the light was fetched and get the new state.
+    ///
+    /// Nothing is sent if no attribute was modified.
+    pub fn update_state(self) -> Res<Self> {
+        if self.state.is_changed() {
+            let state_json = self.state.changes()?.to_string();
+            let response = self
+                .client()
+                .put(&format!("lights/{}/state", self.id()), state_json)?;
+            response_success(&response)?;
+        }
+        Self::get_light(self.client.unwrap(), self.id())
+    }
+
+    /// Send the whole state, including unmodified attributes, and get the new state.
+    pub fn update_full_state(self) -> Res<Self> {
+        let state_json = serde_json::to_string(&self.state)?;
+        let response = self
+            .client()
+            .put(&format!("lights/{}/state", self.id()), state_json)?;
+        response_success(&response)?;
+        Self::get_light(self.client.unwrap(), self.id())
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        let body = json!({ "name": name });
+        self.client()
+            .put(&format!("lights/{}", self.id()), body.to_string())?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    /// Send the name and get the updated light.
+    pub fn update(self) -> Res<Self> {
+        let attributes = json!({ "name": self.name });
+        let response = self
+            .client()
+            .put(&format!("lights/{}", self.id()), attributes.to_string())?;
+        response_success(&response)?;
+        Self::get_light(self.client.unwrap(), self.id())
+    }
+
+    pub fn delete(self) -> Res<()> {
+        self.client().delete(&format!("lights/{}", self.id()))?;
+        Ok(())
+    }
+
+    /// Get the color gamut of the light, `None` if the light has no colors.
+    pub fn gamut(&self) -> Option<Gamut> {
+        let control = &self.capabilities.control;
+        if let Some([red, green, blue]) = control.colorgamut {
+            return Some(Gamut::new(red, green, blue));
+        }
+        control.colorgamuttype.as_deref().and_then(Gamut::from_type)
+    }
+
+    /// Get the color temperature range of the light, `None` if the light has no
+    /// color temperature.
+    pub fn ct_range(&self) -> Option<CtRange> {
+        if let Some(range) = &self.capabilities.control.ct {
+            return Some(range.clone());
+        }
+        self.state
+            .ct
+            .map(|_| CtRange::new(colors::CT_MIN, colors::CT_MAX))
+    }
+
+    /// Set the color of the light state by its name, clamped to the light's gamut
+    /// or color temperature range.
+    pub fn set_named_color(&mut self, name: &str) -> Res<&mut Self> {
+        let (gamut, ct_range) = (self.gamut(), self.ct_range());
+        match colors::named_color(name) {
+            Some(NamedColor::Xy(_)) if gamut.is_none() => {
+                return Err(format!("light {} doesn't support colors", self.id()).into())
+            }
+            Some(NamedColor::Ct(_)) if ct_range.is_none() => {
+                return Err(format!("light {} doesn't support color temperature", self.id()).into())
+            }
+            _ => {}
+        }
+        self.state
+            .set_named_color(name, gamut.as_ref(), ct_range.as_ref())?;
+        Ok(self)
+    }
+
+    /// Get the power-on behavior of the light, `None` if the light doesn't support it.
+    pub fn startup(&self) -> Option<&LightStartup> {
+        self.config.startup.as_ref()
+    }
+
+    /// Set the power-on behavior of the light.
+    ///
+    /// Use `set_startup_custom` for the `custom` mode.
+    pub fn set_startup(&mut self, mode: StartupMode) -> Res<&mut Self> {
+        if mode == StartupMode::Custom {
+            return Err("custom startup mode requires settings, use set_startup_custom".into());
+        }
+        let startup = LightStartup {
+            mode: Some(mode),
+            ..Default::default()
+        };
+        self.put_startup(startup)
+    }
+
+    /// Make the light power on with the brightness and color of the given state.
+    pub fn set_startup_custom(&mut self, state: &LightState) -> Res<&mut Self> {
+        let startup = LightStartup {
+            mode: Some(StartupMode::Custom),
+            configured: None,
+            customsettings: Some(state.into()),
+        };
+        self.put_startup(startup)
+    }
+
+    fn put_startup(&mut self, startup: LightStartup) -> Res<&mut Self> {
+        if self.startup().is_none() {
+            return Err(format!("light {} doesn't support a startup mode", self.id()).into());
+        }
+        let body = json!({ "startup": &startup });
+        let response = self
+            .client()
+            .put(&format!("lights/{}/config", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.config.startup = Some(startup);
+        Ok(self)
+    }
+
+    pub fn id(&self) -> &LightId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn state(&mut self) -> &mut LightState {
+        &mut self.state
+    }
+
+    pub fn ty(&self) -> &str {
+        &self.ty
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn swupdate(&self) -> &LightSWUpdate {
+        &self.swupdate
+    }
+
+    pub fn swversion(&self) -> &str {
+        &self.swversion
+    }
+}
+
+impl<'a, C: HTTPClient + Default> Resource<'a, C> for Light<'a, C> {
+    type Id = LightId;
+
+    const PATH: &'static str = "lights";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<LightId, Self>> {
+        Self::get_lights(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &LightId) -> Res<Self> {
+        Self::get_light(http_client, id)
+    }
+
+    /// Lights are not created, they are added by a search for new lights.
+    fn create(_http_client: &'a C, _attributes: &serde_json::Value) -> Res<Self> {
+        Err("lights are added by a search, not created".into())
+    }
+
+    fn id(&self) -> LightId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_lights {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    #[test]
+    fn get_light_ok() {
+        let response = String::from(
+            r#"
+        {
+            "state": {
+                "on": false,
+                "bri": 144,
+                "alert": "none",
+                "mode": "homeautomation",
+                "reachable": true
+            },
+            "swupdate": {
+                "state": "noupdates",
+                "lastinstall": "2018-11-29T23:31:54"
+            },
+            "type": "Dimmable light",
+            "name": "Hue white lamp 1",
+            "modelid": "LWB010",
+            "manufacturername": "Philips",
+            "productname": "Hue white lamp",
+            "capabilities": {
+                "certified": true,
+                "control": {
+                    "mindimlevel": 5000,
+                    "maxlumen": 806
+                },
+                "streaming": {
+                    "renderer": false,
+                    "proxy": false
+                }
+            },
+            "config": {
+                "archetype": "classicbulb",
+                "function": "functional",
+                "direction": "omnidirectional",
+                "startup": {
+                    "mode": "powerfail",
+                    "configured": true
+                }
+            },
+            "uniqueid": "00:17:88:01:02:24:3a:e8-0b",
+            "swversion": "1.46.13_r26312",
+            "swconfigid": "564ABA6B",
+            "productid": "Philips-LWB010-1-A19DLv3"
+        }"#,
+        );
+
+        let http_client_mock = HTTPClientMock {
+            body: None,
+            return_string: Some(response),
+            error: None,
+            ..Default::default()
+        };
+        let light = Light::get_light(&http_client_mock, &1.into());
+        assert!(light.is_ok());
+    }
+
+    #[test]
+    fn light_named_color() {
+        let response = r#"
+        {
+            "state": {"on": true, "bri": 144, "ct": 366, "alert": "none", "colormode": "ct", "reachable": true},
+            "swupdate": {"state": "noupdates", "lastinstall": "2018-11-29T23:31:54"},
+            "type": "Color temperature light",
+            "name": "Hue ambiance lamp 1",
+            "modelid": "LTW010",
+            "manufacturername": "Philips",
+            "productname": "Hue ambiance lamp",
+            "capabilities": {"control": {"mindimlevel": 1000, "maxlumen": 806, "ct": {"min": 200, "max": 400}}},
+            "uniqueid": "00:17:88:01:02:24:3a:e9-0b",
+            "swversion": "1.46.13_r26312"
+        }"#;
+        let client = HTTPClientMock {
+            return_string: Some(response.to_owned()),
+            ..Default::default()
+        };
+        let mut light = Light::get_light(&client, &1.into()).unwrap();
+        assert_eq!(light.ct_range(), Some(CtRange::new(200, 400)));
+        light.set_named_color("energize").unwrap();
+        assert_eq!(light.state().ct(), 200);
+        assert!(light.set_named_color("red").is_err());
+
+        let dimmable = response
+            .replace(r#""ct": 366, "#, "")
+            .replace(r#", "ct": {"min": 200, "max": 400}"#, "");
+        let client = HTTPClientMock {
+            return_string: Some(dimmable),
+            ..Default::default()
+        };
+        let mut light = Light::get_light(&client, &2.into()).unwrap();
+        assert_eq!(light.ct_range(), None);
+        assert!(light.set_named_color("relax").is_err());
+    }
+
+    #[test]
+    fn light_ids() {
+        let id: LightId = "300".parse().unwrap();
+        assert_eq!(id, "300");
+        assert_eq!(LightId::from(7).to_string(), "7");
+        assert!("".parse::<LightId>().is_err());
+        assert!("3/state".parse::<LightId>().is_err());
+
+        let lights: BTreeMap<LightId, u8> =
+            serde_json::from_str(r#"{"1": 1, "hallway": 2}"#).unwrap();
+        assert_eq!(lights["hallway"], 2);
+        assert!(serde_json::from_str::<BTreeMap<LightId, u8>>(r#"{"a b": 1}"#).is_err());
+    }
+
+    #[test]
+    fn light_startup() {
+        let response = String::from(
+            r#"
+        {
+            "state": {"on": true, "bri": 200, "ct": 366, "xy": [0.4, 0.4], "colormode": "ct"},
+            "swupdate": {"state": "noupdates", "lastinstall": null},
+            "type": "Color temperature light",
+            "name": "Hall",
+            "modelid": "LTW001",
+            "manufacturername": "Philips",
+            "productname": "Hue ambiance lamp",
+            "config": {
+                "archetype": "classicbulb",
+                "startup": {"mode": "powerfail", "configured": true}
+            },
+            "uniqueid": "00:17:88:01:02:24:3a:e9-0b",
+            "swversion": "1.46.13_r26312"
+        }"#,
+        );
+
+        let http_client_mock = HTTPClientMock {
+            body: Some(r#"[{"success":{"/lights/1/config/startup/mode":"safety"}}]"#.to_owned()),
+            return_string: Some(response.clone()),
+            error: None,
+            ..Default::default()
+        };
+        let mut light = Light::get_light(&http_client_mock, &1.into()).unwrap();
+        let startup = light.startup().unwrap();
+        assert_eq!(startup.mode(), Some(&StartupMode::Powerfail));
+        assert!(startup.configured());
+
+        assert!(light.set_startup(StartupMode::Custom).is_err());
+        light.set_startup(StartupMode::Safety).unwrap();
+        assert_eq!(light.startup().unwrap().mode(), Some(&StartupMode::Safety));
+
+        let state = LightStateBuilder::default()
+            .bri(Some(200))
+            .ct(Some(366))
+            .xy(Some([0.4, 0.4]))
+            .colormode(Some(ColorMode::Ct))
+            .build()
+            .unwrap();
+        light.set_startup_custom(&state).unwrap();
+        let startup = light.startup().unwrap();
+        assert_eq!(startup.mode(), Some(&StartupMode::Custom));
+        let settings = startup.customsettings().unwrap();
+        assert_eq!(settings.bri(), Some(200));
+        assert_eq!(settings.ct(), Some(366));
+        assert_eq!(settings.xy(), None);
+
+        let failing = HTTPClientMock {
+            body: Some(
+                r#"[{"error":{"type":6,"address":"/lights/1/config/startup","description":"parameter, startup, not available"}}]"#
+                    .to_owned(),
+            ),
+            return_string: Some(response.clone()),
+            ..Default::default()
+        };
+        let mut light = Light::get_light(&failing, &1.into()).unwrap();
+        assert!(light.set_startup(StartupMode::Safety).is_err());
+        assert_eq!(
+            light.startup().unwrap().mode(),
+            Some(&StartupMode::Powerfail)
+        );
+
+        let unsupported = HTTPClientMock {
+            return_string: Some(response.replace(
+                r#""startup": {"mode": "powerfail", "configured": true}"#,
+                r#""function": "functional""#,
+            )),
+            ..Default::default()
+        };
+        let mut light = Light::get_light(&unsupported, &1.into()).unwrap();
+        assert!(light.startup().is_none());
+        assert!(light.set_startup(StartupMode::Safety).is_err());
+        assert!(unsupported
+            .requests
+            .borrow()
+            .iter()
+            .all(|(method, _, _)| method == "GET"));
+    }
+
+    #[test]
+    fn update_state_changes_only() {
+        let response = String::from(
+            r#"
+        {
+            "state": {"on": false, "bri": 144, "ct": 366, "alert": "none"},
+            "swupdate": {"state": "noupdates", "lastinstall": null},
+            "type": "Color temperature light",
+            "name": "Hall",
+            "modelid": "LTW001",
+            "manufacturername": "Philips",
+            "productname": "Hue ambiance lamp",
+            "uniqueid": "00:17:88:01:02:24:3a:e9-0b",
+            "swversion": "1.46.13_r26312"
+        }"#,
+        );
+        let http_client_mock = HTTPClientMock {
+            body: Some(r#"[{"success":{"/lights/1/state/on":true}}]"#.to_owned()),
+            return_string: Some(response.clone()),
+            ..Default::default()
+        };
+
+        let light = Light::get_light(&http_client_mock, &1.into()).unwrap();
+        let mut light = light.update_state().unwrap();
+        light.state().set_on(true);
+        let light = light.update_state().unwrap();
+        assert_eq!(light.id(), "1");
+        light.update_full_state().unwrap();
+
+        let puts: Vec<(String, String)> = http_client_mock
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, call, body)| (call.clone(), body.clone()))
+            .collect();
+        assert_eq!(
+            puts,
+            vec![
+                ("lights/1/state".to_owned(), r#"{"on":true}"#.to_owned()),
+                (
+                    "lights/1/state".to_owned(),
+                    r#"{"on":false,"bri":144,"alert":"none","ct":366}"#.to_owned()
+                ),
+            ]
+        );
+
+        let failing = HTTPClientMock {
+            body: Some(
+                r#"[{"error":{"type":201,"address":"/lights/1/state/bri","description":"parameter, bri, is not modifiable. Device is set to off."}}]"#
+                    .to_owned(),
+            ),
+            return_string: Some(response),
+            ..Default::default()
+        };
+        let mut light = Light::get_light(&failing, &1.into()).unwrap();
+        light.state().set_bri(200);
+        assert!(light.update_state().is_err());
+        let light = Light::get_light(&failing, &1.into()).unwrap();
+        assert!(light.update_full_state().is_err());
+    }
+
+    #[test]
+    fn get_light_err() {
+        let response = String::from("not expected response");
+        let http_client_mock = HTTPClientMock {
+            body: None,
+            return_string: Some(response),
+            error: None,
+            ..Default::default()
+        };
+        let light = Light::get_light(&http_client_mock, &1.into());
+        assert!(light.is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Operations shared by all the resources of the bridge, for tools that work
+//! on any resource type, e.g. a backup of the bridge.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+
+/// Resource of the bridge stored in a collection, e.g. the lights.
+///
+/// # Examples
+///
+/// ```no_run
+/// use hue_core::lights::Light;
+/// use hue_core::resource::Resource;
+/// use hue_core::*;
+///
+/// fn names<'a, C: HTTPClient + Default, R: Resource<'a, C>>(client: &'a C) -> Res<Vec<String>> {
+///     Ok(R::list(client)?.values().map(|r| r.name().to_owned()).collect())
+/// }
+///
+/// fn example<C: HTTPClient + Default>(client: &C) {
+///     println!("{:?}", names::<_, Light<_>>(client).unwrap());
+/// }
+/// ```
+pub trait Resource<'a, C: HTTPClient + Default + 'a>: Serialize + Sized {
+    type Id: Clone + Ord + fmt::Display + FromStr + Serialize;
+
+    /// Path of the collection, e.g. `lights`.
+    const PATH: &'static str;
+
+    /// Get all the resources by their ID.
+    fn list(http_client: &'a C) -> Res<BTreeMap<Self::Id, Self>>;
+
+    fn get(http_client: &'a C, id: &Self::Id) -> Res<Self>;
+
+    /// Create a resource from its attributes and get it.
+    fn create(http_client: &'a C, attributes: &Value) -> Res<Self> {
+        capabilities::ensure_available(http_client, &[(Self::PATH, 1)])?;
+        let response = http_client.post(Self::PATH, attributes.to_string())?;
+        let id = created_id(&response)?;
+        let id = id
+            .parse()
+            .map_err(|_| format!("invalid ID {} of {}", id, Self::PATH))?;
+        Self::get(http_client, &id)
+    }
+
+    fn id(&self) -> Self::Id;
+
+    fn name(&self) -> &str;
+
+    /// Path of the resource, e.g. `lights/3`.
+    fn path(&self) -> String {
+        format!("{}/{}", Self::PATH, self.id())
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self>;
+
+    /// Send the attributes of the resource and get the updated resource.
+    fn update(self) -> Res<Self>;
+
+    fn delete(self) -> Res<()>;
+}
+
+#[cfg(test)]
+mod tests_resource {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+    use crate::groups::Group;
+    use crate::lights::Light;
+    use crate::resourcelinks::ResourceLink;
+
+    const GROUP: &str = r#"
+        {
+            "name": "Living",
+            "lights": ["3"],
+            "sensors": [],
+            "type": "Room",
+            "state": {"all_on": false, "any_on": false},
+            "recycle": false,
+            "class": "Living room",
+            "action": {"on": false}
+        }"#;
+
+    const RESOURCELINK: &str = r#"
+        {
+            "name": "Hall motion",
+            "type": "Link",
+            "classid": 10010,
+            "owner": "83b7780291a6ceffbe0bd049104df",
+            "links": ["/sensors/2"]
+        }"#;
+
+    fn client() -> HTTPClientMock {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"2"}}]"#.to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        client
+            .responses
+            .insert("groups".to_owned(), format!(r#"{{"2": {}}}"#, GROUP));
+        client
+            .responses
+            .insert("groups/2".to_owned(), GROUP.to_owned());
+        client
+            .responses
+            .insert("resourcelinks/2".to_owned(), RESOURCELINK.to_owned());
+        client
+    }
+
+    fn rename<'a, R: Resource<'a, HTTPClientMock>>(client: &'a HTTPClientMock) -> String {
+        let id = "2".parse().ok().unwrap();
+        let mut resource = R::get(client, &id).unwrap();
+        resource.rename("Hall").unwrap();
+        assert_eq!(resource.name(), "Hall");
+        resource.path()
+    }
+
+    #[test]
+    fn generic_operations() {
+        let client = client();
+        let groups = <Group<_> as Resource<_>>::list(&client).unwrap();
+        assert_eq!(Resource::id(&groups["2"]), "2");
+
+        assert_eq!(rename::<Group<_>>(&client), "groups/2");
+        assert_eq!(rename::<ResourceLink<_>>(&client), "resourcelinks/2");
+        let puts: Vec<String> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, path, _)| path.clone())
+            .collect();
+        assert_eq!(puts, vec!["groups/2", "resourcelinks/2"]);
+    }
+
+    #[test]
+    fn create() {
+        let client = client();
+        let attributes = json!({ "name": "Living", "lights": ["3"] });
+        let group = <Group<_> as Resource<_>>::create(&client, &attributes).unwrap();
+        assert_eq!(Resource::id(&group), "2");
+        assert_eq!(
+            client.requests.borrow()[1],
+            (
+                "POST".to_owned(),
+                "groups".to_owned(),
+                r#"{"lights":["3"],"name":"Living"}"#.to_owned()
+            )
+        );
+        assert!(<Light<_> as Resource<_>>::create(&client, &attributes).is_err());
+    }
+}
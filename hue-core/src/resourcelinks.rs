@@ -0,0 +1,399 @@
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::groups::GroupId;
+use super::lights::LightId;
+use super::resource::Resource;
+use super::rules::RuleId;
+use super::scenes::SceneId;
+use super::schedules::ScheduleId;
+use super::sensors::SensorId;
+use super::*;
+
+string_id! {
+    /// ID of a resourcelink, e.g. `5400`.
+    pub struct ResourceLinkId;
+}
+
+/// Bridge resource referenced by a resourcelink, as in `/lights/3`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LinkTarget {
+    Light(LightId),
+    Group(GroupId),
+    Scene(SceneId),
+    Sensor(SensorId),
+    Rule(RuleId),
+    Schedule(ScheduleId),
+    ResourceLink(ResourceLinkId),
+    /// Address of a resource unknown to this version of the crate.
+    Other(String),
+}
+
+impl LinkTarget {
+    /// Path of the resource as used by the API calls, e.g. `lights/3`.
+    pub fn path(&self) -> String {
+        self.to_string().trim_start_matches('/').to_owned()
+    }
+}
+
+impl FromStr for LinkTarget {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Res<Self> {
+        let (resource, id) = match s.strip_prefix('/').and_then(|s| s.split_once('/')) {
+            Some(parts) => parts,
+            None => return Err(format!("invalid link {}, expected /<resource>/<id>", s).into()),
+        };
+        Ok(match resource {
+            "lights" => LinkTarget::Light(id.parse()?),
+            "groups" => LinkTarget::Group(id.parse()?),
+            "scenes" => LinkTarget::Scene(id.parse()?),
+            "sensors" => LinkTarget::Sensor(id.parse()?),
+            "rules" => LinkTarget::Rule(id.parse()?),
+            "schedules" => LinkTarget::Schedule(id.parse()?),
+            "resourcelinks" => LinkTarget::ResourceLink(id.parse()?),
+            _ => LinkTarget::Other(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for LinkTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkTarget::Light(id) => write!(f, "/lights/{}", id),
+            LinkTarget::Group(id) => write!(f, "/groups/{}", id),
+            LinkTarget::Scene(id) => write!(f, "/scenes/{}", id),
+            LinkTarget::Sensor(id) => write!(f, "/sensors/{}", id),
+            LinkTarget::Rule(id) => write!(f, "/rules/{}", id),
+            LinkTarget::Schedule(id) => write!(f, "/schedules/{}", id),
+            LinkTarget::ResourceLink(id) => write!(f, "/resourcelinks/{}", id),
+            LinkTarget::Other(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+impl Serialize for LinkTarget {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LinkTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let link = String::deserialize(deserializer)?;
+        link.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResourceLink<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<ResourceLinkId>,
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(rename = "type")]
+    ty: String,
+    classid: u16,
+    owner: String,
+    #[serde(default)]
+    recycle: bool,
+    links: Vec<LinkTarget>,
+}
+
+/// API for operations on the resourcelinks.
+impl<'a, C: HTTPClient + Default> ResourceLink<'a, C> {
+    pub fn get_resourcelinks(http_client: &'a C) -> Res<BTreeMap<ResourceLinkId, Self>> {
+        let resp = http_client.get("resourcelinks")?;
+        let mut resourcelinks: BTreeMap<ResourceLinkId, Self> = serde_json::from_str(&resp)?;
+        for (id, resourcelink) in resourcelinks.iter_mut() {
+            resourcelink.id = Some(id.clone());
+            resourcelink.client = Some(http_client);
+        }
+        Ok(resourcelinks)
+    }
+
+    pub fn get_resourcelink(http_client: &'a C, id: &ResourceLinkId) -> Res<Self> {
+        let response = http_client.get(&format!("resourcelinks/{}", id))?;
+        let mut resourcelink: Self = serde_json::from_str(&response)?;
+        resourcelink.id = Some(id.clone());
+        resourcelink.client = Some(http_client);
+        Ok(resourcelink)
+    }
+
+    /// Create a resourcelink, `classid` identifies the kind of link for the owning app.
+    pub fn create_resourcelink(
+        http_client: &'a C,
+        name: String,
+        classid: u16,
+        links: Vec<LinkTarget>,
+    ) -> Res<Self> {
+        let body = json!({
+            "name": name,
+            "classid": classid,
+            "links": links,
+        });
+        capabilities::ensure_available(http_client, &[("resourcelinks", 1)])?;
+        let response = http_client.post("resourcelinks", body.to_string())?;
+        let id = created_id(&response)?;
+        Self::get_resourcelink(http_client, &id.parse()?)
+    }
+
+    pub fn delete_resourcelink(http_client: &'a C, id: &ResourceLinkId) -> Res<()> {
+        let response = http_client.delete(&format!("resourcelinks/{}", id))?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Send the name, description and links and get the updated resourcelink.
+    pub fn update(self) -> Res<Self> {
+        let attributes = json!({
+            "name": self.name,
+            "description": self.description,
+            "links": self.links,
+        });
+        let response = self.client().put(
+            &format!("resourcelinks/{}", self.id()),
+            attributes.to_string(),
+        )?;
+        response_success(&response)?;
+        Self::get_resourcelink(self.client.unwrap(), self.id())
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        let body = json!({ "name": name });
+        let response = self
+            .client()
+            .put(&format!("resourcelinks/{}", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    pub fn delete(self) -> Res<()> {
+        Self::delete_resourcelink(self.client.unwrap(), self.id())
+    }
+
+    /// Delete the resourcelink and the linked resources it owns.
+    ///
+    /// A linked resource is deleted if it is recyclable and, for resources
+    /// with an owner, has the same owner as the resourcelink. Returns the
+    /// deleted resources.
+    pub fn delete_with_children(self) -> Res<Vec<LinkTarget>> {
+        let mut deleted = vec![];
+        for link in &self.links {
+            let response = self.client().get(&link.path())?;
+            let resource: serde_json::Value = serde_json::from_str(&response)?;
+            let recyclable = resource["recycle"].as_bool().unwrap_or(false);
+            let owned = resource["owner"]
+                .as_str()
+                .is_none_or(|owner| owner == self.owner);
+            if recyclable && owned {
+                let response = self.client().delete(&link.path())?;
+                response_success(&response)?;
+                deleted.push(link.clone());
+            }
+        }
+        Self::delete_resourcelink(self.client.unwrap(), self.id())?;
+        Ok(deleted)
+    }
+
+    /// Set the description, sent with `update`.
+    pub fn set_description(&mut self, description: &str) -> &mut Self {
+        self.description = description.to_owned();
+        self
+    }
+
+    /// Replace the links, sent with `update`.
+    pub fn set_links(&mut self, links: Vec<LinkTarget>) -> &mut Self {
+        self.links = links;
+        self
+    }
+
+    pub fn id(&self) -> &ResourceLinkId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn classid(&self) -> u16 {
+        self.classid
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn recycle(&self) -> bool {
+        self.recycle
+    }
+
+    pub fn links(&self) -> &[LinkTarget] {
+        &self.links
+    }
+}
+
+impl<'a, C: HTTPClient + Default> Resource<'a, C> for ResourceLink<'a, C> {
+    type Id = ResourceLinkId;
+
+    const PATH: &'static str = "resourcelinks";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<ResourceLinkId, Self>> {
+        Self::get_resourcelinks(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &ResourceLinkId) -> Res<Self> {
+        Self::get_resourcelink(http_client, id)
+    }
+
+    fn id(&self) -> ResourceLinkId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_resourcelinks {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const RESOURCELINK: &str = r#"
+        {
+            "name": "Hall motion",
+            "description": "Motion sensor setup",
+            "type": "Link",
+            "classid": 10010,
+            "owner": "83b7780291a6ceffbe0bd049104df",
+            "recycle": false,
+            "links": [
+                "/sensors/2",
+                "/rules/1",
+                "/rules/2",
+                "/scenes/abc",
+                "/schedules/3",
+                "/lights/1"
+            ]
+        }"#;
+
+    fn client() -> HTTPClientMock {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"5400"}}]"#.to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        let responses = [
+            ("resourcelinks/5400", RESOURCELINK),
+            ("sensors/2", r#"{"name": "Hall sensor", "recycle": true}"#),
+            (
+                "rules/1",
+                r#"{"owner": "83b7780291a6ceffbe0bd049104df", "recycle": true}"#,
+            ),
+            ("rules/2", r#"{"owner": "another", "recycle": true}"#),
+            (
+                "scenes/abc",
+                r#"{"owner": "83b7780291a6ceffbe0bd049104df", "recycle": false}"#,
+            ),
+            ("schedules/3", r#"{"recycle": true}"#),
+            ("lights/1", r#"{"name": "Hall"}"#),
+        ];
+        for (path, response) in responses.iter() {
+            client
+                .responses
+                .insert(path.to_string(), response.to_string());
+        }
+        client
+    }
+
+    #[test]
+    fn link_targets() {
+        let link: LinkTarget = "/scenes/abc".parse().unwrap();
+        assert_eq!(link, LinkTarget::Scene("abc".parse().unwrap()));
+        assert_eq!(link.path(), "scenes/abc");
+        assert_eq!(LinkTarget::Rule(7.into()).to_string(), "/rules/7");
+        assert_eq!(
+            "/capabilities/x".parse::<LinkTarget>().unwrap(),
+            LinkTarget::Other("/capabilities/x".to_owned())
+        );
+        assert!("lights/3".parse::<LinkTarget>().is_err());
+        assert!("/lights/3/state".parse::<LinkTarget>().is_err());
+    }
+
+    #[test]
+    fn create_resourcelink_ok() {
+        let client = client();
+        let links = vec![LinkTarget::Sensor(2.into()), LinkTarget::Rule(1.into())];
+        let link =
+            ResourceLink::create_resourcelink(&client, "Hall motion".to_owned(), 10010, links)
+                .unwrap();
+        assert_eq!(link.id(), "5400");
+        assert_eq!(link.links().len(), 6);
+        assert_eq!(
+            client.requests.borrow()[1],
+            (
+                "POST".to_owned(),
+                "resourcelinks".to_owned(),
+                r#"{"classid":10010,"links":["/sensors/2","/rules/1"],"name":"Hall motion"}"#
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn delete_with_children() {
+        let client = client();
+        let link = ResourceLink::get_resourcelink(&client, &5400.into()).unwrap();
+        let deleted = link.delete_with_children().unwrap();
+        assert_eq!(
+            deleted,
+            vec![
+                LinkTarget::Sensor(2.into()),
+                LinkTarget::Rule(1.into()),
+                LinkTarget::Schedule(3.into())
+            ]
+        );
+        let deletes: Vec<String> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "DELETE")
+            .map(|(_, path, _)| path.clone())
+            .collect();
+        assert_eq!(
+            deletes,
+            vec!["sensors/2", "rules/1", "schedules/3", "resourcelinks/5400"]
+        );
+    }
+}
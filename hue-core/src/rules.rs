@@ -0,0 +1,583 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::resource::Resource;
+use super::*;
+
+string_id! {
+    /// ID of a rule, e.g. `1`.
+    pub struct RuleId;
+}
+
+/// Maximum number of conditions and of actions in a rule.
+pub const RULE_MAX_ITEMS: usize = 8;
+
+string_enum! {
+    /// Comparison of a condition.
+    pub enum Operator {
+        Eq => "eq",
+        Gt => "gt",
+        Lt => "lt",
+        /// Attribute changed.
+        Dx => "dx",
+        /// Attribute changed and then unchanged for the given delay.
+        Ddx => "ddx",
+        /// Attribute unchanged for the given time.
+        Stable => "stable",
+        /// Attribute changed within the given time.
+        NotStable => "not stable",
+        /// Time is in the given interval.
+        In => "in",
+        /// Time is not in the given interval.
+        NotIn => "not in",
+    }
+}
+
+impl Operator {
+    /// Whether the operator compares to a value.
+    pub fn takes_value(&self) -> bool {
+        *self != Operator::Dx
+    }
+}
+
+string_enum! {
+    pub enum RuleStatus {
+        Enabled => "enabled",
+        Disabled => "disabled",
+        /// Rule was disabled because a resource it references was deleted.
+        ResourceDeleted => "resourcedeleted",
+    }
+}
+
+string_enum! {
+    /// HTTP method of a rule action.
+    pub enum Method {
+        Put => "PUT",
+        Post => "POST",
+        Delete => "DELETE",
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Condition {
+    address: String,
+    operator: Operator,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+}
+
+impl Condition {
+    /// Create a condition, e.g. `/sensors/2/state/presence` `eq` `true`.
+    pub fn new(address: &str, operator: Operator, value: Option<&str>) -> Res<Self> {
+        match (operator.takes_value(), value) {
+            (true, None) => return Err(format!("operator {} needs a value", operator).into()),
+            (false, Some(_)) => {
+                return Err(format!("operator {} doesn't take a value", operator).into())
+            }
+            _ => (),
+        }
+        Ok(Condition {
+            address: address.to_owned(),
+            operator,
+            value: value.map(|value| value.to_owned()),
+        })
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn operator(&self) -> &Operator {
+        &self.operator
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Action {
+    address: String,
+    method: Method,
+    body: serde_json::Value,
+}
+
+impl Action {
+    /// Create an action, e.g. `/groups/1/action` `PUT` `{"on": true}`.
+    pub fn new(address: &str, method: Method, body: serde_json::Value) -> Self {
+        Action {
+            address: address.to_owned(),
+            method,
+            body,
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    pub fn body(&self) -> &serde_json::Value {
+        &self.body
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Rule<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<RuleId>,
+    name: String,
+    owner: String,
+    #[serde(with = "crate::timestamp", default)]
+    created: Option<NaiveDateTime>,
+    #[serde(with = "crate::timestamp", default)]
+    lasttriggered: Option<NaiveDateTime>,
+    timestriggered: u32,
+    status: RuleStatus,
+    #[serde(default)]
+    recycle: Option<bool>,
+    conditions: Vec<Condition>,
+    actions: Vec<Action>,
+}
+
+/// API for operations on the rules.
+impl<'a, C: HTTPClient + Default> Rule<'a, C> {
+    pub fn get_rules(http_client: &'a C) -> Res<BTreeMap<RuleId, Self>> {
+        let resp = http_client.get("rules")?;
+        let mut rules: BTreeMap<RuleId, Self> = serde_json::from_str(&resp)?;
+        for (id, rule) in rules.iter_mut() {
+            rule.id = Some(id.clone());
+            rule.client = Some(http_client);
+        }
+        Ok(rules)
+    }
+
+    pub fn get_rule(http_client: &'a C, id: &RuleId) -> Res<Self> {
+        let response = http_client.get(&format!("rules/{}", id))?;
+        let mut rule: Self = serde_json::from_str(&response)?;
+        rule.id = Some(id.clone());
+        rule.client = Some(http_client);
+        Ok(rule)
+    }
+
+    /// Create a rule after checking that its addresses reference existing resources.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hue_core::rules::*;
+    /// use hue_core::*;
+    ///
+    /// fn example<C: HTTPClient + Default>(client: &C) {
+    ///     let conditions = vec![
+    ///         Condition::new("/sensors/2/state/presence", Operator::Eq, Some("true")).unwrap(),
+    ///         Condition::new("/sensors/2/state/presence", Operator::Dx, None).unwrap(),
+    ///     ];
+    ///     let actions = vec![Action::new(
+    ///         "/groups/1/action",
+    ///         Method::Put,
+    ///         serde_json::json!({ "on": true }),
+    ///     )];
+    ///     Rule::create_rule(client, "Hall motion".to_owned(), conditions, actions).unwrap();
+    /// }
+    /// ```
+    pub fn create_rule(
+        http_client: &'a C,
+        name: String,
+        conditions: Vec<Condition>,
+        actions: Vec<Action>,
+    ) -> Res<Self> {
+        validate(http_client, &conditions, &actions)?;
+        capabilities::ensure_available(
+            http_client,
+            &[
+                ("rules", 1),
+                ("conditions", conditions.len() as u32),
+                ("actions", actions.len() as u32),
+            ],
+        )?;
+        let body = json!({
+            "name": name,
+            "conditions": conditions,
+            "actions": actions,
+        });
+        let response = http_client.post("rules", body.to_string())?;
+        let id = created_id(&response)?;
+        Self::get_rule(http_client, &id.parse()?)
+    }
+
+    pub fn delete_rule(http_client: &'a C, id: &RuleId) -> Res<()> {
+        let response = http_client.delete(&format!("rules/{}", id))?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Send the name, status, conditions and actions and get the updated rule.
+    pub fn update(self) -> Res<Self> {
+        validate(self.client(), &self.conditions, &self.actions)?;
+        let mut attributes = json!({
+            "name": self.name,
+            "conditions": self.conditions,
+            "actions": self.actions,
+        });
+        if self.status != RuleStatus::ResourceDeleted {
+            attributes["status"] = json!(self.status);
+        }
+        let response = self
+            .client()
+            .put(&format!("rules/{}", self.id()), attributes.to_string())?;
+        response_success(&response)?;
+        Self::get_rule(self.client.unwrap(), self.id())
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        let body = json!({ "name": name });
+        let response = self
+            .client()
+            .put(&format!("rules/{}", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) -> Res<&mut Self> {
+        let status = if enabled {
+            RuleStatus::Enabled
+        } else {
+            RuleStatus::Disabled
+        };
+        let body = json!({ "status": status });
+        let response = self
+            .client()
+            .put(&format!("rules/{}", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.status = status;
+        Ok(self)
+    }
+
+    pub fn delete(self) -> Res<()> {
+        Self::delete_rule(self.client.unwrap(), self.id())
+    }
+
+    /// Replace the conditions, sent with `update`.
+    pub fn set_conditions(&mut self, conditions: Vec<Condition>) -> &mut Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// Replace the actions, sent with `update`.
+    pub fn set_actions(&mut self, actions: Vec<Action>) -> &mut Self {
+        self.actions = actions;
+        self
+    }
+
+    pub fn id(&self) -> &RuleId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn status(&self) -> &RuleStatus {
+        &self.status
+    }
+
+    pub fn timestriggered(&self) -> u32 {
+        self.timestriggered
+    }
+
+    pub fn created(&self) -> Option<NaiveDateTime> {
+        self.created
+    }
+
+    pub fn lasttriggered(&self) -> Option<NaiveDateTime> {
+        self.lasttriggered
+    }
+
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+}
+
+/// Check the number of conditions and actions and that the lights, groups
+/// and sensors in their addresses exist.
+pub fn validate<C: HTTPClient>(
+    http_client: &C,
+    conditions: &[Condition],
+    actions: &[Action],
+) -> Res<()> {
+    if conditions.is_empty() || conditions.len() > RULE_MAX_ITEMS {
+        return Err(format!("a rule needs 1 to {} conditions", RULE_MAX_ITEMS).into());
+    }
+    if actions.is_empty() || actions.len() > RULE_MAX_ITEMS {
+        return Err(format!("a rule needs 1 to {} actions", RULE_MAX_ITEMS).into());
+    }
+
+    let addresses = conditions
+        .iter()
+        .map(|condition| condition.address())
+        .chain(actions.iter().map(|action| action.address()));
+    let mut existing: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for address in addresses {
+        let mut parts = address.split('/');
+        if parts.next() != Some("") {
+            return Err(format!("address {} must start with /", address).into());
+        }
+        let (resource, id) = match (parts.next(), parts.next()) {
+            (Some(resource), Some(id)) => (resource, id),
+            _ => continue,
+        };
+        if !["lights", "groups", "sensors"].contains(&resource) {
+            continue;
+        }
+        // group 0 contains all the lights and isn't listed
+        if resource == "groups" && id == "0" {
+            continue;
+        }
+        if !existing.contains_key(resource) {
+            let response = http_client.get(resource)?;
+            let resources: BTreeMap<String, serde_json::Value> = serde_json::from_str(&response)?;
+            existing.insert(resource, resources.into_keys().collect());
+        }
+        if !existing[resource].contains(id) {
+            return Err(format!("address {} references a missing resource", address).into());
+        }
+    }
+    Ok(())
+}
+
+impl<'a, C: HTTPClient + Default> Resource<'a, C> for Rule<'a, C> {
+    type Id = RuleId;
+
+    const PATH: &'static str = "rules";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<RuleId, Self>> {
+        Self::get_rules(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &RuleId) -> Res<Self> {
+        Self::get_rule(http_client, id)
+    }
+
+    /// Create a rule from its name, conditions and actions, see `Rule::create_rule`.
+    fn create(http_client: &'a C, attributes: &serde_json::Value) -> Res<Self> {
+        let name = attributes["name"].as_str().ok_or("rule without a name")?;
+        let conditions = serde_json::from_value(attributes["conditions"].clone())?;
+        let actions = serde_json::from_value(attributes["actions"].clone())?;
+        Self::create_rule(http_client, name.to_owned(), conditions, actions)
+    }
+
+    fn id(&self) -> RuleId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_rules {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const RULE: &str = r#"
+        {
+            "name": "Hall motion",
+            "owner": "83b7780291a6ceffbe0bd049104df",
+            "created": "2019-06-01T20:11:42",
+            "lasttriggered": "2019-06-02T19:24:41",
+            "timestriggered": 12,
+            "status": "enabled",
+            "recycle": false,
+            "conditions": [
+                {"address": "/sensors/2/state/presence", "operator": "eq", "value": "true"},
+                {"address": "/sensors/2/state/presence", "operator": "dx"},
+                {"address": "/config/localtime", "operator": "in", "value": "T20:00:00/T08:00:00"}
+            ],
+            "actions": [
+                {"address": "/groups/1/action", "method": "PUT", "body": {"scene": "abc"}}
+            ]
+        }"#;
+
+    fn client() -> HTTPClientMock {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"1"}}]"#.to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        client
+            .responses
+            .insert("rules".to_owned(), format!(r#"{{"1": {}}}"#, RULE));
+        client
+            .responses
+            .insert("rules/1".to_owned(), RULE.to_owned());
+        client
+            .responses
+            .insert("sensors".to_owned(), r#"{"1": {}, "2": {}}"#.to_owned());
+        client
+            .responses
+            .insert("groups".to_owned(), r#"{"1": {}}"#.to_owned());
+        client
+            .responses
+            .insert("lights".to_owned(), r#"{"1": {}, "2": {}}"#.to_owned());
+        client
+    }
+
+    fn conditions() -> Vec<Condition> {
+        vec![
+            Condition::new("/sensors/2/state/presence", Operator::Eq, Some("true")).unwrap(),
+            Condition::new("/sensors/2/state/presence", Operator::Dx, None).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn get_rules_ok() {
+        let client = client();
+        let rules = Rule::get_rules(&client).unwrap();
+        let rule = &rules["1"];
+        assert_eq!(rule.status(), &RuleStatus::Enabled);
+        assert_eq!(rule.timestriggered(), 12);
+        assert_eq!(rule.conditions()[1].operator(), &Operator::Dx);
+        assert_eq!(rule.conditions()[1].value(), None);
+        assert_eq!(rule.conditions()[2].operator(), &Operator::In);
+        assert_eq!(rule.actions()[0].method(), &Method::Put);
+    }
+
+    #[test]
+    fn condition_value() {
+        assert!(Condition::new("/sensors/2/state/presence", Operator::Dx, Some("true")).is_err());
+        assert!(Condition::new("/sensors/2/state/presence", Operator::Eq, None).is_err());
+        let condition = Condition::new(
+            "/sensors/2/state/presence",
+            Operator::NotStable,
+            Some("PT00:01:00"),
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&condition).unwrap(),
+            r#"{"address":"/sensors/2/state/presence","operator":"not stable","value":"PT00:01:00"}"#
+        );
+    }
+
+    #[test]
+    fn ddx_condition() {
+        assert!(Condition::new("/sensors/2/state/presence", Operator::Ddx, None).is_err());
+        let condition = Condition::new(
+            "/sensors/2/state/presence",
+            Operator::Ddx,
+            Some("PT00:05:00"),
+        )
+        .unwrap();
+        assert_eq!(condition.value(), Some("PT00:05:00"));
+    }
+
+    #[test]
+    fn create_rule_ok() {
+        let client = client();
+        let actions = vec![
+            Action::new("/groups/1/action", Method::Put, json!({ "on": true })),
+            Action::new(
+                "/groups/0/action",
+                Method::Put,
+                json!({ "alert": "select" }),
+            ),
+        ];
+        let rule =
+            Rule::create_rule(&client, "Hall motion".to_owned(), conditions(), actions).unwrap();
+        assert_eq!(rule.id(), "1");
+
+        let requests = client.requests.borrow();
+        let (_, path, body) = requests
+            .iter()
+            .find(|(method, _, _)| method == "POST")
+            .unwrap();
+        assert_eq!(path, "rules");
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(
+            body["conditions"][1],
+            json!({"address": "/sensors/2/state/presence", "operator": "dx"})
+        );
+        assert_eq!(body["actions"][0]["body"], json!({ "on": true }));
+    }
+
+    #[test]
+    fn create_rule_missing_resource() {
+        let client = client();
+        let actions = vec![Action::new(
+            "/lights/3/state",
+            Method::Put,
+            json!({ "on": true }),
+        )];
+        assert!(Rule::create_rule(&client, "Hall".to_owned(), conditions(), actions).is_err());
+
+        let actions = vec![Action::new(
+            "/groups/1/action",
+            Method::Put,
+            json!({ "on": true }),
+        )];
+        let conditions =
+            vec![Condition::new("/sensors/5/state/presence", Operator::Dx, None).unwrap()];
+        assert!(Rule::create_rule(&client, "Hall".to_owned(), conditions, actions).is_err());
+        assert!(client
+            .requests
+            .borrow()
+            .iter()
+            .all(|(method, _, _)| method == "GET"));
+    }
+
+    #[test]
+    fn update_rule() {
+        let client = client();
+        let mut rule = Rule::get_rule(&client, &1.into()).unwrap();
+        rule.set_enabled(false).unwrap();
+        assert_eq!(rule.status(), &RuleStatus::Disabled);
+        rule.set_conditions(conditions());
+        rule.update().unwrap();
+
+        let requests = client.requests.borrow();
+        let puts: Vec<&String> = requests
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, _, body)| body)
+            .collect();
+        assert_eq!(puts[0], r#"{"status":"disabled"}"#);
+        let body: serde_json::Value = serde_json::from_str(puts[1]).unwrap();
+        assert_eq!(body["status"], "disabled");
+        assert_eq!(body["conditions"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,1089 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use super::groups::{Group, GroupId};
+use super::lights::{Light, LightId, LightState, StateDiff};
+use super::resource::Resource;
+use super::*;
+
+string_id! {
+    /// ID of a scene, e.g. `Zk8HKQkTDd3k9pq`.
+    pub struct SceneId;
+}
+
+string_enum! {
+    /// Kind of a scene.
+    pub enum SceneType {
+        /// Scene of an arbitrary set of lights.
+        LightScene => "LightScene",
+        /// Scene of a group, lights follow the group membership.
+        GroupScene => "GroupScene",
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AppData {
+    version: Option<u8>,
+    data: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Scene<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<SceneId>,
+    name: String,
+    #[serde(rename = "type")]
+    ty: SceneType,
+    group: Option<GroupId>,
+    lights: Vec<LightId>,
+    owner: String,
+    recycle: bool,
+    locked: bool,
+    appdata: Option<AppData>,
+    #[serde(default)]
+    picture: String,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+    version: u8,
+    // only returned when getting a single scene
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    lightstates: BTreeMap<LightId, LightState>,
+}
+
+/// API for operations on the scenes.
+impl<'a, C: HTTPClient + Default + Debug> Scene<'a, C> {
+    pub fn get_scenes(http_client: &'a C) -> Res<BTreeMap<SceneId, Self>> {
+        let resp: String = http_client.get("scenes")?;
+        let mut scenes: BTreeMap<SceneId, Self> = serde_json::from_str(&resp)?;
+
+        for (id, scene) in scenes.iter_mut() {
+            scene.id = Some(id.clone());
+            scene.client = Some(http_client);
+        }
+
+        Ok(scenes)
+    }
+
+    /// Get a scene including the states of its lights.
+    pub fn get_scene(http_client: &'a C, id: &SceneId) -> Res<Self> {
+        let response = http_client.get(&format!("scenes/{}", id))?;
+        let mut scene: Self = serde_json::from_str(&response)?;
+        scene.id = Some(id.clone());
+        scene.client = Some(http_client);
+        Ok(scene)
+    }
+
+    /// Create a scene of the lights from their current state.
+    ///
+    /// Scenes with `recycle` set can be removed by the bridge when it runs out of space.
+    pub fn create_light_scene(
+        http_client: &'a C,
+        name: String,
+        lights: Vec<LightId>,
+        recycle: bool,
+    ) -> Res<Self> {
+        let body = json!({
+            "name": name,
+            "type": SceneType::LightScene,
+            "lights": lights,
+            "recycle": recycle,
+        });
+        Self::create_scene(http_client, body)
+    }
+
+    /// Create a scene of the group from the current state of its lights.
+    pub fn create_group_scene(
+        http_client: &'a C,
+        name: String,
+        group: &GroupId,
+        recycle: bool,
+    ) -> Res<Self> {
+        let body = json!({
+            "name": name,
+            "type": SceneType::GroupScene,
+            "group": group,
+            "recycle": recycle,
+        });
+        Self::create_scene(http_client, body)
+    }
+
+    /// Save the current state of the lights as a light scene.
+    ///
+    /// An existing light scene with the name is updated with the lights and
+    /// their current state if `overwrite` is set, otherwise it is an error.
+    pub fn save_light_scene(
+        http_client: &'a C,
+        name: String,
+        lights: Vec<LightId>,
+        overwrite: bool,
+    ) -> Res<Self> {
+        let existing = Self::get_scenes(http_client)?
+            .into_values()
+            .find(|scene| scene.name == name && scene.ty == SceneType::LightScene);
+        match existing {
+            Some(mut scene) if overwrite => {
+                scene.set_lights(&lights)?.store_current_state()?;
+                Ok(scene)
+            }
+            Some(scene) => Err(format!("scene {} exists as {}", name, scene.id()).into()),
+            None => Self::create_light_scene(http_client, name, lights, false),
+        }
+    }
+
+    /// Save the current state of the group's lights as a group scene.
+    ///
+    /// An existing scene of the group with the name gets the current state
+    /// if `overwrite` is set, otherwise it is an error.
+    pub fn save_group_scene(
+        http_client: &'a C,
+        name: String,
+        group: &GroupId,
+        overwrite: bool,
+    ) -> Res<Self> {
+        let existing = Self::get_scenes(http_client)?
+            .into_values()
+            .find(|scene| scene.name == name && scene.group.as_ref() == Some(group));
+        match existing {
+            Some(mut scene) if overwrite => {
+                scene.store_current_state()?;
+                Ok(scene)
+            }
+            Some(scene) => Err(format!("scene {} exists as {}", name, scene.id()).into()),
+            None => Self::create_group_scene(http_client, name, group, false),
+        }
+    }
+
+    /// Create a scene if the bridge has room for it and the light states of its lights.
+    fn create_scene(http_client: &'a C, body: serde_json::Value) -> Res<Self> {
+        let lights = match body["group"].as_str() {
+            Some(group) => Group::get_group(http_client, &group.parse()?)?
+                .lights()
+                .len(),
+            None => body["lights"].as_array().map_or(0, |lights| lights.len()),
+        };
+        capabilities::ensure_available(
+            http_client,
+            &[("scenes", 1), ("lightstates", lights as u32)],
+        )?;
+        let response = http_client.post("scenes", body.to_string())?;
+        let id = created_id(&response)?;
+        Self::get_scene(http_client, &id.parse()?)
+    }
+
+    pub fn delete_scene(http_client: &'a C, id: &SceneId) -> Res<()> {
+        let response = http_client.delete(&format!("scenes/{}", id))?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Send the name and, for a light scene, the lights and get the updated scene.
+    pub fn update(self) -> Res<Self> {
+        let mut attributes = json!({ "name": self.name });
+        if self.ty == SceneType::LightScene {
+            attributes["lights"] = json!(self.lights);
+        }
+        self.put_attributes(attributes)?;
+        Self::get_scene(self.client.unwrap(), self.id())
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.put_attributes(json!({ "name": name }))?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    /// Replace the lights of a light scene, lights of a group scene follow its group.
+    pub fn set_lights(&mut self, lights: &[LightId]) -> Res<&mut Self> {
+        if self.ty != SceneType::LightScene {
+            return Err(format!("lights of {} scene can't be set", self.ty).into());
+        }
+        self.put_attributes(json!({ "lights": lights }))?;
+        self.lights = lights.to_vec();
+        Ok(self)
+    }
+
+    /// Store the current state of the scene's lights as the scene's light states.
+    pub fn store_current_state(&mut self) -> Res<&mut Self> {
+        self.put_attributes(json!({ "storelightstate": true }))?;
+        self.lightstates.clear();
+        Ok(self)
+    }
+
+    /// Set the state a light gets when the scene is recalled.
+    pub fn set_lightstate(&mut self, light: &LightId, state: &LightState) -> Res<&mut Self> {
+        let mut body = serde_json::to_value(state)?;
+        if let Some(attributes) = body.as_object_mut() {
+            // alerts are not stored in scenes
+            attributes.remove("alert");
+        }
+        let response = self.client().put(
+            &format!("scenes/{}/lightstates/{}", self.id(), light),
+            body.to_string(),
+        )?;
+        response_success(&response)?;
+        self.lightstates.insert(light.clone(), state.clone());
+        Ok(self)
+    }
+
+    fn put_attributes(&self, attributes: serde_json::Value) -> Res<()> {
+        let response = self
+            .client()
+            .put(&format!("scenes/{}", self.id()), attributes.to_string())?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    pub fn delete(self) -> Res<()> {
+        Self::delete_scene(self.client.unwrap(), self.id())
+    }
+
+    /// Find a scene by its ID or name.
+    ///
+    /// Names are not unique, `group` limits the search to scenes of the group.
+    /// Finding several scenes with the name is an error.
+    pub fn find_scene(http_client: &'a C, name_or_id: &str, group: Option<&GroupId>) -> Res<Self> {
+        let mut scenes = Self::get_scenes(http_client)?;
+        if let Some(scene) = scenes.remove(name_or_id) {
+            return Ok(scene);
+        }
+
+        let mut found: Vec<Self> = scenes
+            .into_values()
+            .filter(|scene| scene.name == name_or_id)
+            .filter(|scene| group.is_none() || scene.group.as_ref() == group)
+            .collect();
+        match found.len() {
+            0 => Err(format!("scene {} not found", name_or_id).into()),
+            1 => Ok(found.remove(0)),
+            _ => {
+                let ids: Vec<&str> = found.iter().map(|scene| scene.id().as_str()).collect();
+                Err(format!("several scenes named {}: {}", name_or_id, ids.join(", ")).into())
+            }
+        }
+    }
+
+    /// Recall the scene on its group, or on all the lights for a scene without a group.
+    ///
+    /// `transition` overrides the scene's transition time, in multiples of 100ms.
+    pub fn recall(&self, transition: Option<u16>) -> Res<()> {
+        let group_id = self.group.clone().unwrap_or_else(GroupId::all);
+        let group = Group::get_group(self.client.unwrap(), &group_id)?;
+        group.recall_scene(self.id(), transition)
+    }
+
+    /// Compare the scene's light states to the current state of its lights.
+    ///
+    /// Gets the light states of the scene if it doesn't have them yet. Lights
+    /// missing on the bridge are left out.
+    pub fn diff(&self) -> Res<BTreeMap<LightId, StateDiff>> {
+        let mut lights = Light::get_lights(self.client.unwrap())?;
+        self.diff_lights(&mut lights)
+    }
+
+    pub(crate) fn diff_lights(
+        &self,
+        lights: &mut BTreeMap<LightId, Light<'a, C>>,
+    ) -> Res<BTreeMap<LightId, StateDiff>> {
+        let fetched;
+        let lightstates = if self.lightstates.is_empty() {
+            fetched = Self::get_scene(self.client.unwrap(), self.id())?;
+            &fetched.lightstates
+        } else {
+            &self.lightstates
+        };
+        Ok(lightstates
+            .iter()
+            .filter_map(|(id, expected)| {
+                lights
+                    .get_mut(id)
+                    .map(|light| (id.clone(), light.state().diff(expected)))
+            })
+            .collect())
+    }
+
+    pub fn id(&self) -> &SceneId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ty(&self) -> &SceneType {
+        &self.ty
+    }
+
+    /// Group of a group scene.
+    pub fn group(&self) -> Option<&GroupId> {
+        self.group.as_ref()
+    }
+
+    pub fn lights(&self) -> &[LightId] {
+        &self.lights
+    }
+
+    /// States of the lights by light ID, only available for scenes from `get_scene`.
+    pub fn lightstates(&self) -> &BTreeMap<LightId, LightState> {
+        &self.lightstates
+    }
+
+    pub fn recycle(&self) -> bool {
+        self.recycle
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+
+    /// Time of the last update, in UTC like all the timestamps of the bridge.
+    pub fn lastupdated_utc(&self) -> Option<DateTime<Utc>> {
+        self.lastupdated.map(|time| time.and_utc())
+    }
+}
+
+/// Scene that refers to lights and its group by name instead of ID, so it can
+/// be edited by hand and imported to another bridge.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortableScene {
+    name: String,
+    #[serde(rename = "type")]
+    ty: SceneType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    lightstates: BTreeMap<String, LightState>,
+}
+
+impl PortableScene {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ty(&self) -> &SceneType {
+        &self.ty
+    }
+
+    /// Name of the group of a group scene.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// States of the lights by light name.
+    pub fn lightstates(&self) -> &BTreeMap<String, LightState> {
+        &self.lightstates
+    }
+}
+
+/// Outcome of a scene export.
+#[derive(Debug, Default)]
+pub struct ExportReport {
+    scenes: Vec<PortableScene>,
+    unmatched_lights: Vec<(String, LightId)>,
+}
+
+impl ExportReport {
+    /// Exported scenes.
+    pub fn scenes(&self) -> &[PortableScene] {
+        &self.scenes
+    }
+
+    /// Lights that are in a scene but missing on the bridge, as (scene name,
+    /// light ID). These lights are left out of the exported scene.
+    pub fn unmatched_lights(&self) -> &[(String, LightId)] {
+        &self.unmatched_lights
+    }
+}
+
+/// Outcome of a scene import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    created: Vec<(String, SceneId)>,
+    unmatched_lights: Vec<(String, String)>,
+    unmatched_groups: Vec<(String, String)>,
+    failed_lights: Vec<(String, String, String)>,
+}
+
+impl ImportReport {
+    /// Created scenes as (scene name, scene ID).
+    pub fn created(&self) -> &[(String, SceneId)] {
+        &self.created
+    }
+
+    /// Lights that are missing on the bridge or whose name is not unique,
+    /// as (scene name, light name). These lights are left out of the scene.
+    pub fn unmatched_lights(&self) -> &[(String, String)] {
+        &self.unmatched_lights
+    }
+
+    /// Groups that are missing on the bridge or whose name is not unique,
+    /// as (scene name, group name). These scenes are not imported.
+    pub fn unmatched_groups(&self) -> &[(String, String)] {
+        &self.unmatched_groups
+    }
+
+    /// Light states the bridge rejected, as (scene name, light name, error).
+    /// The scenes are created without these states.
+    pub fn failed_lights(&self) -> &[(String, String, String)] {
+        &self.failed_lights
+    }
+}
+
+/// Map names to IDs, names used by several resources map to `None`.
+fn ids_by_name<'n, K: Clone + 'n, I: Iterator<Item = (&'n K, &'n str)>>(
+    resources: I,
+) -> BTreeMap<String, Option<K>> {
+    let mut ids: BTreeMap<String, Option<K>> = BTreeMap::new();
+    for (id, name) in resources {
+        ids.entry(name.to_owned())
+            .and_modify(|id| *id = None)
+            .or_insert_with(|| Some(id.clone()));
+    }
+    ids
+}
+
+/// Get all the scenes with their light states, referring to lights and groups by name.
+pub fn export_scenes<C: HTTPClient + Default + Debug>(http_client: &C) -> Res<ExportReport> {
+    let light_names: BTreeMap<LightId, String> = Light::get_lights(http_client)?
+        .into_iter()
+        .map(|(id, light)| (id, light.name().to_owned()))
+        .collect();
+    let group_names: BTreeMap<GroupId, String> = Group::get_groups(http_client)?
+        .into_iter()
+        .map(|(id, group)| (id, group.name().to_owned()))
+        .collect();
+
+    let mut report = ExportReport::default();
+    for id in Scene::get_scenes(http_client)?.keys() {
+        let scene = Scene::get_scene(http_client, id)?;
+        let mut lightstates = BTreeMap::new();
+        for (light, state) in &scene.lightstates {
+            match light_names.get(light) {
+                Some(name) => {
+                    lightstates.insert(name.clone(), state.clone());
+                }
+                None => report
+                    .unmatched_lights
+                    .push((scene.name.clone(), light.clone())),
+            }
+        }
+        report.scenes.push(PortableScene {
+            name: scene.name.clone(),
+            ty: scene.ty.clone(),
+            group: scene
+                .group
+                .as_ref()
+                .and_then(|group| group_names.get(group).cloned()),
+            lightstates,
+        });
+    }
+    Ok(report)
+}
+
+/// Create the scenes on the bridge, matching lights and groups by name.
+pub fn import_scenes<C: HTTPClient + Default + Debug>(
+    http_client: &C,
+    scenes: &[PortableScene],
+) -> Res<ImportReport> {
+    let lights = Light::get_lights(http_client)?;
+    let light_ids = ids_by_name(lights.iter().map(|(id, light)| (id, light.name())));
+    let groups = Group::get_groups(http_client)?;
+    let group_ids = ids_by_name(groups.iter().map(|(id, group)| (id, group.name())));
+
+    let mut report = ImportReport::default();
+    for portable in scenes {
+        let mut states = Vec::new();
+        for (light_name, state) in &portable.lightstates {
+            match light_ids.get(light_name) {
+                Some(Some(id)) => states.push((id.clone(), light_name, state)),
+                _ => report
+                    .unmatched_lights
+                    .push((portable.name.clone(), light_name.clone())),
+            }
+        }
+
+        let mut scene = match (&portable.ty, &portable.group) {
+            (SceneType::GroupScene, Some(group_name)) => match group_ids.get(group_name) {
+                Some(Some(id)) => {
+                    Scene::create_group_scene(http_client, portable.name.clone(), id, false)?
+                }
+                _ => {
+                    report
+                        .unmatched_groups
+                        .push((portable.name.clone(), group_name.clone()));
+                    continue;
+                }
+            },
+            _ if states.is_empty() => continue,
+            _ => Scene::create_light_scene(
+                http_client,
+                portable.name.clone(),
+                states.iter().map(|(id, _, _)| id.clone()).collect(),
+                false,
+            )?,
+        };
+        for (id, light_name, state) in states {
+            if let Err(e) = scene.set_lightstate(&id, state) {
+                report.failed_lights.push((
+                    portable.name.clone(),
+                    light_name.clone(),
+                    e.to_string(),
+                ));
+            }
+        }
+        report
+            .created
+            .push((portable.name.clone(), scene.id().clone()));
+    }
+    Ok(report)
+}
+
+impl<'a, C: HTTPClient + Default + Debug> Resource<'a, C> for Scene<'a, C> {
+    type Id = SceneId;
+
+    const PATH: &'static str = "scenes";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<SceneId, Self>> {
+        Self::get_scenes(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &SceneId) -> Res<Self> {
+        Self::get_scene(http_client, id)
+    }
+
+    /// Create a scene, the bridge needs room for a light state of each of its lights.
+    fn create(http_client: &'a C, attributes: &serde_json::Value) -> Res<Self> {
+        Self::create_scene(http_client, attributes.clone())
+    }
+
+    fn id(&self) -> SceneId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_scenes {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const SCENES: &str = r#"
+        {
+            "4e1c6b20e-on-0": {
+                "name": "Kathy on 1449133269486",
+                "type": "LightScene",
+                "lights": ["2", "3"],
+                "owner": "ffffffffe0341b1b376a2389376a2389",
+                "recycle": true,
+                "locked": false,
+                "appdata": {},
+                "picture": "",
+                "lastupdated": "2015-12-03T08:57:13",
+                "version": 1
+            },
+            "Zk8HKQkTDd3k9pq": {
+                "name": "Relax",
+                "type": "GroupScene",
+                "group": "2",
+                "lights": ["3"],
+                "owner": "ffffffffe0341b1b376a2389376a2389",
+                "recycle": false,
+                "locked": false,
+                "appdata": {"version": 1, "data": "Q7Ay1_r02_d08"},
+                "picture": "",
+                "lastupdated": "2019-05-19T08:28:59",
+                "version": 2
+            }
+        }"#;
+
+    const GROUP: &str = r#"
+        {
+            "name": "Living",
+            "lights": ["3"],
+            "sensors": [],
+            "type": "Room",
+            "state": {"all_on": false, "any_on": false},
+            "recycle": false,
+            "class": "Living room",
+            "action": {"on": false}
+        }"#;
+
+    fn client() -> HTTPClientMock {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{}}]"#.to_owned()),
+            return_string: Some(GROUP.to_owned()),
+            ..Default::default()
+        };
+        client
+            .responses
+            .insert("scenes".to_owned(), SCENES.to_owned());
+        client
+    }
+
+    fn puts(client: &HTTPClientMock) -> Vec<(String, String)> {
+        client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "PUT")
+            .map(|(_, call, body)| (call.clone(), body.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn get_scenes_ok() {
+        let client = client();
+        let scenes = Scene::get_scenes(&client).unwrap();
+        let scene = &scenes["Zk8HKQkTDd3k9pq"];
+        assert_eq!(scene.id(), "Zk8HKQkTDd3k9pq");
+        assert_eq!(scene.name(), "Relax");
+        assert_eq!(scene.group().unwrap(), "2");
+    }
+
+    #[test]
+    fn find_scene() {
+        let client = client();
+        let scene = Scene::find_scene(&client, "4e1c6b20e-on-0", None).unwrap();
+        assert_eq!(scene.ty(), &SceneType::LightScene);
+        let scene = Scene::find_scene(&client, "Relax", Some(&2.into())).unwrap();
+        assert_eq!(scene.id(), "Zk8HKQkTDd3k9pq");
+        assert!(Scene::find_scene(&client, "Relax", Some(&1.into())).is_err());
+    }
+
+    const SCENE: &str = r#"
+        {
+            "name": "Relax",
+            "type": "GroupScene",
+            "group": "2",
+            "lights": ["3", "4"],
+            "owner": "ffffffffe0341b1b376a2389376a2389",
+            "recycle": false,
+            "locked": false,
+            "appdata": {},
+            "picture": "",
+            "lastupdated": "2019-05-19T08:28:59",
+            "version": 2,
+            "lightstates": {
+                "3": {"on": true, "bri": 144, "ct": 447},
+                "4": {"on": false}
+            }
+        }"#;
+
+    #[test]
+    fn get_scene_lightstates() {
+        let client = HTTPClientMock {
+            return_string: Some(SCENE.to_owned()),
+            ..Default::default()
+        };
+        let scene = Scene::get_scene(&client, &"Zk8HKQkTDd3k9pq".parse().unwrap()).unwrap();
+        assert_eq!(scene.id(), "Zk8HKQkTDd3k9pq");
+        assert_eq!(scene.lightstates().len(), 2);
+        assert_eq!(scene.lightstates()["3"].ct(), 447);
+        assert_eq!(
+            scene.lastupdated_utc().unwrap().to_rfc3339(),
+            "2019-05-19T08:28:59+00:00"
+        );
+        assert!(!scene.lightstates()["4"].on());
+    }
+
+    #[test]
+    fn create_scenes() {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"Zk8HKQkTDd3k9pq"}}]"#.to_owned()),
+            return_string: Some(SCENE.to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        client
+            .responses
+            .insert("groups/2".to_owned(), GROUP.to_owned());
+        let scene = Scene::create_light_scene(
+            &client,
+            "Reading".to_owned(),
+            vec![3.into(), 4.into()],
+            true,
+        )
+        .unwrap();
+        assert_eq!(scene.id(), "Zk8HKQkTDd3k9pq");
+        Scene::create_group_scene(&client, "Relax".to_owned(), &2.into(), false).unwrap();
+
+        let posts: Vec<serde_json::Value> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method == "POST")
+            .map(|(_, _, body)| serde_json::from_str(body).unwrap())
+            .collect();
+        assert_eq!(
+            posts,
+            vec![
+                json!({"name": "Reading", "type": "LightScene", "lights": ["3", "4"], "recycle": true}),
+                json!({"name": "Relax", "type": "GroupScene", "group": "2", "recycle": false}),
+            ]
+        );
+    }
+
+    #[test]
+    fn scene_lightstates_update() {
+        let client = HTTPClientMock {
+            body: Some(r#"[{"success":{}}]"#.to_owned()),
+            return_string: Some(SCENE.to_owned()),
+            ..Default::default()
+        };
+        let mut scene = Scene::get_scene(&client, &"Zk8HKQkTDd3k9pq".parse().unwrap()).unwrap();
+        let mut state = lights::LightStateBuilder::default()
+            .on(Some(true))
+            .bri(Some(254))
+            .build()
+            .unwrap();
+        state.set_alert(lights::Alert::Select);
+        scene.set_lightstate(&4.into(), &state).unwrap();
+        assert_eq!(scene.lightstates()["4"].bri(), 254);
+        scene.store_current_state().unwrap();
+        assert!(scene.lightstates().is_empty());
+        scene.delete().unwrap();
+
+        assert_eq!(
+            puts(&client),
+            vec![
+                (
+                    "scenes/Zk8HKQkTDd3k9pq/lightstates/4".to_owned(),
+                    r#"{"bri":254,"on":true}"#.to_owned()
+                ),
+                (
+                    "scenes/Zk8HKQkTDd3k9pq".to_owned(),
+                    r#"{"storelightstate":true}"#.to_owned()
+                ),
+            ]
+        );
+        let requests = client.requests.borrow();
+        assert_eq!(
+            requests.last().unwrap().1,
+            "scenes/Zk8HKQkTDd3k9pq".to_owned()
+        );
+        assert_eq!(requests.last().unwrap().0, "DELETE");
+    }
+
+    #[test]
+    fn save_scenes() {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"abc"}}]"#.to_owned()),
+            return_string: Some(SCENE.to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        client
+            .responses
+            .insert("scenes".to_owned(), SCENES.to_owned());
+        client
+            .responses
+            .insert("groups/1".to_owned(), GROUP.to_owned());
+
+        assert!(Scene::save_group_scene(&client, "Relax".to_owned(), &2.into(), false).is_err());
+        let scene = Scene::save_group_scene(&client, "Relax".to_owned(), &2.into(), true).unwrap();
+        assert_eq!(scene.id(), "Zk8HKQkTDd3k9pq");
+        Scene::save_group_scene(&client, "Relax".to_owned(), &1.into(), false).unwrap();
+
+        let scene = Scene::save_light_scene(
+            &client,
+            "Kathy on 1449133269486".to_owned(),
+            vec![2.into()],
+            true,
+        )
+        .unwrap();
+        assert_eq!(scene.lights(), ["2"]);
+
+        let requests: Vec<(String, String)> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method != "GET")
+            .map(|(method, call, _)| (method.clone(), call.clone()))
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                ("PUT".to_owned(), "scenes/Zk8HKQkTDd3k9pq".to_owned()),
+                ("POST".to_owned(), "scenes".to_owned()),
+                ("PUT".to_owned(), "scenes/4e1c6b20e-on-0".to_owned()),
+                ("PUT".to_owned(), "scenes/4e1c6b20e-on-0".to_owned()),
+            ]
+        );
+    }
+
+    const LIGHTS: &str = r#"
+        {
+            "3": {
+                "state": {"on": false},
+                "swupdate": {"state": "noupdates", "lastinstall": null},
+                "type": "Color temperature light",
+                "name": "Sofa",
+                "modelid": "LTW001",
+                "manufacturername": "Philips",
+                "productname": "Hue ambiance lamp",
+                "uniqueid": "00:17:88:01:02:24:3a:e9-0b",
+                "swversion": "1.46.13_r26312"
+            },
+            "4": {
+                "state": {"on": false},
+                "swupdate": {"state": "noupdates", "lastinstall": null},
+                "type": "Color temperature light",
+                "name": "Desk",
+                "modelid": "LTW001",
+                "manufacturername": "Philips",
+                "productname": "Hue ambiance lamp",
+                "uniqueid": "00:17:88:01:02:24:3a:ea-0b",
+                "swversion": "1.46.13_r26312"
+            }
+        }"#;
+
+    fn export_client() -> HTTPClientMock {
+        let mut client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"new"}}]"#.to_owned()),
+            return_string: Some(SCENE.to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        client.responses.insert(
+            "scenes".to_owned(),
+            json!({ "Zk8HKQkTDd3k9pq": serde_json::from_str::<serde_json::Value>(SCENE).unwrap() })
+                .to_string(),
+        );
+        client
+            .responses
+            .insert("lights".to_owned(), LIGHTS.to_owned());
+        client.responses.insert(
+            "groups".to_owned(),
+            json!({ "2": serde_json::from_str::<serde_json::Value>(GROUP).unwrap() }).to_string(),
+        );
+        client
+            .responses
+            .insert("groups/2".to_owned(), GROUP.to_owned());
+        client
+    }
+
+    #[test]
+    fn export_import_scenes() {
+        let client = export_client();
+        let report = export_scenes(&client).unwrap();
+        assert!(report.unmatched_lights().is_empty());
+        let exported = report.scenes();
+        assert_eq!(exported.len(), 1);
+        let scene = &exported[0];
+        assert_eq!(scene.name(), "Relax");
+        assert_eq!(scene.group(), Some("Living"));
+        assert_eq!(
+            scene.lightstates().keys().collect::<Vec<_>>(),
+            vec!["Desk", "Sofa"]
+        );
+
+        let yaml = serde_yaml::to_string(&exported).unwrap();
+        let yaml = yaml.replace("Desk", "Lamp");
+        let imported: Vec<PortableScene> = serde_yaml::from_str(&yaml).unwrap();
+
+        let report = import_scenes(&client, &imported).unwrap();
+        assert_eq!(
+            report.created(),
+            [("Relax".to_owned(), "new".parse().unwrap())]
+        );
+        assert_eq!(
+            report.unmatched_lights(),
+            [("Relax".to_owned(), "Lamp".to_owned())]
+        );
+        assert!(report.unmatched_groups().is_empty());
+
+        let requests = client.requests.borrow();
+        let writes: Vec<(&str, &str)> = requests
+            .iter()
+            .filter(|(method, _, _)| method != "GET")
+            .map(|(method, call, _)| (method.as_str(), call.as_str()))
+            .collect();
+        assert_eq!(
+            writes,
+            vec![("POST", "scenes"), ("PUT", "scenes/new/lightstates/3"),]
+        );
+    }
+
+    #[test]
+    fn export_unmatched_light() {
+        let mut client = export_client();
+        client.return_string = Some(SCENE.replace(
+            r#""4": {"on": false}"#,
+            r#""4": {"on": false, "transitiontime": 10}, "5": {"on": true}"#,
+        ));
+        let report = export_scenes(&client).unwrap();
+        assert_eq!(
+            report.unmatched_lights(),
+            [("Relax".to_owned(), "5".parse().unwrap())]
+        );
+        let scene = &report.scenes()[0];
+        assert_eq!(scene.lightstates()["Desk"].transitiontime(), Some(10));
+        let yaml = serde_yaml::to_string(report.scenes()).unwrap();
+        assert!(yaml.contains("transitiontime: 10"));
+    }
+
+    #[test]
+    fn import_failed_light() {
+        let mut client = export_client();
+        client.put_responses.insert(
+            "scenes/new/lightstates/3".to_owned(),
+            r#"[{"error":{"type":7,"address":"/scenes/new/lightstates/3/bri","description":"invalid value"}}]"#
+                .to_owned(),
+        );
+        let scenes: Vec<PortableScene> = serde_yaml::from_str(
+            r#"
+- name: Relax
+  type: LightScene
+  lightstates:
+    Sofa: {on: true, bri: 100}
+    Desk: {on: false}
+"#,
+        )
+        .unwrap();
+        let report = import_scenes(&client, &scenes).unwrap();
+        assert_eq!(
+            report.created(),
+            [("Relax".to_owned(), "new".parse().unwrap())]
+        );
+        assert_eq!(report.failed_lights().len(), 1);
+        let (scene, light, error) = &report.failed_lights()[0];
+        assert_eq!((scene.as_str(), light.as_str()), ("Relax", "Sofa"));
+        assert!(error.contains("invalid value"));
+    }
+
+    #[test]
+    fn import_unmatched_group() {
+        let client = export_client();
+        let scenes: Vec<PortableScene> = serde_yaml::from_str(
+            r#"
+- name: Relax
+  type: GroupScene
+  group: Attic
+  lightstates:
+    Sofa: {on: true, bri: 100}
+"#,
+        )
+        .unwrap();
+        let report = import_scenes(&client, &scenes).unwrap();
+        assert!(report.created().is_empty());
+        assert_eq!(
+            report.unmatched_groups(),
+            [("Relax".to_owned(), "Attic".to_owned())]
+        );
+    }
+
+    #[test]
+    fn scene_diff() {
+        let lights = LIGHTS.replace(
+            r#""state": {"on": false},
+                "swupdate": {"state": "noupdates", "lastinstall": null},
+                "type": "Color temperature light",
+                "name": "Sofa""#,
+            r#""state": {"on": true, "bri": 100, "ct": 447, "colormode": "ct"},
+                "swupdate": {"state": "noupdates", "lastinstall": null},
+                "type": "Color temperature light",
+                "name": "Sofa""#,
+        );
+        let mut client = export_client();
+        client.responses.insert("lights".to_owned(), lights);
+
+        let scene = Scene::get_scenes(&client)
+            .unwrap()
+            .remove("Zk8HKQkTDd3k9pq")
+            .unwrap();
+        let diff = scene.diff().unwrap();
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff["3"].bri(), -44);
+        assert_eq!(diff["3"].ct(), Some(0));
+        assert_eq!(diff["4"], StateDiff::default());
+    }
+
+    #[test]
+    fn group_active_scene() {
+        let sofa = |state: &str| {
+            LIGHTS.replace(
+                r#""state": {"on": false},
+                "swupdate": {"state": "noupdates", "lastinstall": null},
+                "type": "Color temperature light",
+                "name": "Sofa""#,
+                &format!(
+                    r#""state": {},
+                "swupdate": {{"state": "noupdates", "lastinstall": null}},
+                "type": "Color temperature light",
+                "name": "Sofa""#,
+                    state
+                ),
+            )
+        };
+        let mut client = export_client();
+        client
+            .responses
+            .insert("groups/2".to_owned(), GROUP.to_owned());
+        let tolerance = lights::Tolerance::default();
+
+        client.responses.insert(
+            "lights".to_owned(),
+            sofa(r#"{"on": true, "bri": 145, "ct": 447, "colormode": "ct"}"#),
+        );
+        let group = Group::get_group(&client, &2.into()).unwrap();
+        let scene = group.active_scene(&tolerance).unwrap().unwrap();
+        assert_eq!(scene.id(), "Zk8HKQkTDd3k9pq");
+
+        client.responses.insert(
+            "lights".to_owned(),
+            sofa(r#"{"on": true, "bri": 254, "ct": 447, "colormode": "ct"}"#),
+        );
+        let group = Group::get_group(&client, &2.into()).unwrap();
+        assert!(group.active_scene(&tolerance).unwrap().is_none());
+    }
+
+    #[test]
+    fn recall_scene() {
+        let client = client();
+        let scenes = Scene::get_scenes(&client).unwrap();
+
+        scenes["Zk8HKQkTDd3k9pq"].recall(None).unwrap();
+        scenes["4e1c6b20e-on-0"].recall(Some(4)).unwrap();
+        assert_eq!(
+            puts(&client),
+            vec![
+                (
+                    "groups/2/action".to_owned(),
+                    r#"{"scene":"Zk8HKQkTDd3k9pq"}"#.to_owned()
+                ),
+                (
+                    "groups/0/action".to_owned(),
+                    r#"{"scene":"4e1c6b20e-on-0","transitiontime":4}"#.to_owned()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,762 @@
+use chrono::{NaiveDateTime, NaiveTime, Weekday};
+use serde::de::Deserializer;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::BitOr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::groups::{GroupAction, GroupId};
+use super::lights::{LightId, LightState};
+use super::resource::Resource;
+use super::rules::Method;
+use super::*;
+
+string_id! {
+    /// ID of a schedule, e.g. `1`.
+    pub struct ScheduleId;
+}
+
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const TIME_FORMAT: &str = "%H:%M:%S";
+
+/// Days of the week as the bitmask `0MTWTFSS` of recurring time patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weekdays(u8);
+
+impl Weekdays {
+    pub const MONDAY: Weekdays = Weekdays(64);
+    pub const TUESDAY: Weekdays = Weekdays(32);
+    pub const WEDNESDAY: Weekdays = Weekdays(16);
+    pub const THURSDAY: Weekdays = Weekdays(8);
+    pub const FRIDAY: Weekdays = Weekdays(4);
+    pub const SATURDAY: Weekdays = Weekdays(2);
+    pub const SUNDAY: Weekdays = Weekdays(1);
+    pub const WORKDAYS: Weekdays = Weekdays(124);
+    pub const WEEKEND: Weekdays = Weekdays(3);
+    pub const ALL: Weekdays = Weekdays(127);
+
+    /// Days from a bitmask, at least one day must be set.
+    pub fn from_bits(bits: u8) -> Res<Self> {
+        if bits == 0 || bits > 127 {
+            return Err(format!("invalid weekdays {}, expected 1-127", bits).into());
+        }
+        Ok(Weekdays(bits))
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, days: Weekdays) -> bool {
+        self.0 & days.0 == days.0
+    }
+}
+
+impl From<Weekday> for Weekdays {
+    fn from(day: Weekday) -> Self {
+        Weekdays(1 << (6 - day.num_days_from_monday()))
+    }
+}
+
+impl BitOr for Weekdays {
+    type Output = Weekdays;
+
+    fn bitor(self, other: Weekdays) -> Weekdays {
+        Weekdays(self.0 | other.0)
+    }
+}
+
+/// Time of a schedule as used by `localtime`.
+///
+/// The random offset is the maximum time added to the given time,
+/// e.g. `2026-10-18T07:00:00A00:30:00` triggers between 7:00 and 7:30.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimePattern {
+    /// Once at a date and time, `2026-10-18T07:00:00`.
+    Absolute {
+        time: NaiveDateTime,
+        random: Option<Duration>,
+    },
+    /// At a time on the given days, `W124/T07:00:00`.
+    Recurring {
+        weekdays: Weekdays,
+        time: NaiveTime,
+        random: Option<Duration>,
+    },
+    /// Once after a time, `PT00:10:00`.
+    Timer {
+        duration: Duration,
+        random: Option<Duration>,
+    },
+    /// Repeatedly after a time, `R05/PT00:00:30`, forever if `count` is `None`.
+    RecurringTimer {
+        count: Option<u8>,
+        duration: Duration,
+        random: Option<Duration>,
+    },
+    /// A pattern that couldn't be parsed, e.g. a legacy one, kept as the bridge sent it.
+    Other(String),
+}
+
+impl TimePattern {
+    pub fn absolute(time: NaiveDateTime) -> Self {
+        TimePattern::Absolute { time, random: None }
+    }
+
+    pub fn recurring(weekdays: Weekdays, time: NaiveTime) -> Self {
+        TimePattern::Recurring {
+            weekdays,
+            time,
+            random: None,
+        }
+    }
+
+    pub fn timer(duration: Duration) -> Res<Self> {
+        check_duration(duration)?;
+        Ok(TimePattern::Timer {
+            duration,
+            random: None,
+        })
+    }
+
+    pub fn recurring_timer(count: Option<u8>, duration: Duration) -> Res<Self> {
+        check_duration(duration)?;
+        check_count(count)?;
+        Ok(TimePattern::RecurringTimer {
+            count,
+            duration,
+            random: None,
+        })
+    }
+
+    /// Add a random offset of up to `random` to the time.
+    pub fn randomized(mut self, random: Duration) -> Res<Self> {
+        check_duration(random)?;
+        match &mut self {
+            TimePattern::Absolute { random: r, .. }
+            | TimePattern::Recurring { random: r, .. }
+            | TimePattern::Timer { random: r, .. }
+            | TimePattern::RecurringTimer { random: r, .. } => *r = Some(random),
+            TimePattern::Other(pattern) => {
+                return Err(format!("can't randomize time pattern {}", pattern).into())
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn random(&self) -> Option<Duration> {
+        match self {
+            TimePattern::Absolute { random, .. }
+            | TimePattern::Recurring { random, .. }
+            | TimePattern::Timer { random, .. }
+            | TimePattern::RecurringTimer { random, .. } => *random,
+            TimePattern::Other(_) => None,
+        }
+    }
+}
+
+fn check_duration(duration: Duration) -> Res<()> {
+    if duration.as_secs() >= 24 * 3600 || duration.subsec_nanos() != 0 {
+        return Err(format!(
+            "invalid duration {:?}, expected whole seconds below 24 hours",
+            duration
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn check_count(count: Option<u8>) -> Res<()> {
+    if count.is_some_and(|count| count == 0 || count > 99) {
+        return Err("recurring timer count must be 1-99".into());
+    }
+    Ok(())
+}
+
+fn parse_duration(hms: &str) -> Res<Duration> {
+    let time = NaiveTime::parse_from_str(hms, TIME_FORMAT)
+        .map_err(|e| format!("invalid time {}: {}", hms, e))?;
+    Ok(Duration::from_secs(
+        time.signed_duration_since(NaiveTime::MIN).num_seconds() as u64,
+    ))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Split `<time>A<random>` into the time and the random offset.
+fn split_random(pattern: &str) -> Res<(&str, Option<Duration>)> {
+    match pattern.split_once('A') {
+        Some((time, random)) => Ok((time, Some(parse_duration(random)?))),
+        None => Ok((pattern, None)),
+    }
+}
+
+impl FromStr for TimePattern {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Res<Self> {
+        let (pattern, random) = split_random(s)?;
+        if let Some(recurring) = pattern.strip_prefix('R') {
+            let (count, duration) = match recurring.split_once("/PT") {
+                Some(parts) => parts,
+                None => return Err(format!("invalid recurring timer {}", s).into()),
+            };
+            let count = match count {
+                "" => None,
+                count => Some(count.parse()?),
+            };
+            check_count(count)?;
+            Ok(TimePattern::RecurringTimer {
+                count,
+                duration: parse_duration(duration)?,
+                random,
+            })
+        } else if let Some(duration) = pattern.strip_prefix("PT") {
+            Ok(TimePattern::Timer {
+                duration: parse_duration(duration)?,
+                random,
+            })
+        } else if let Some(recurring) = pattern.strip_prefix('W') {
+            let (bits, time) = match recurring.split_once("/T") {
+                Some(parts) => parts,
+                None => return Err(format!("invalid recurring time {}", s).into()),
+            };
+            Ok(TimePattern::Recurring {
+                weekdays: Weekdays::from_bits(bits.parse()?)?,
+                time: NaiveTime::parse_from_str(time, TIME_FORMAT)?,
+                random,
+            })
+        } else {
+            Ok(TimePattern::Absolute {
+                time: NaiveDateTime::parse_from_str(pattern, DATE_TIME_FORMAT)
+                    .map_err(|e| format!("invalid time pattern {}: {}", s, e))?,
+                random,
+            })
+        }
+    }
+}
+
+impl fmt::Display for TimePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimePattern::Absolute { time, .. } => write!(f, "{}", time.format(DATE_TIME_FORMAT))?,
+            TimePattern::Recurring { weekdays, time, .. } => {
+                write!(f, "W{:03}/T{}", weekdays.bits(), time.format(TIME_FORMAT))?
+            }
+            TimePattern::Timer { duration, .. } => write!(f, "PT{}", format_duration(*duration))?,
+            TimePattern::RecurringTimer {
+                count, duration, ..
+            } => match count {
+                Some(count) => write!(f, "R{:02}/PT{}", count, format_duration(*duration))?,
+                None => write!(f, "R/PT{}", format_duration(*duration))?,
+            },
+            TimePattern::Other(pattern) => write!(f, "{}", pattern)?,
+        }
+        if let Some(random) = self.random() {
+            write!(f, "A{}", format_duration(random))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for TimePattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Ok(pattern.parse().unwrap_or(TimePattern::Other(pattern)))
+    }
+}
+
+string_enum! {
+    pub enum ScheduleStatus {
+        Enabled => "enabled",
+        Disabled => "disabled",
+    }
+}
+
+/// API call made when a schedule triggers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Command {
+    address: String,
+    method: Method,
+    body: serde_json::Value,
+}
+
+impl Command {
+    /// Create a command, the address includes the username, e.g. `/api/<username>/groups/1/action`.
+    pub fn new(address: &str, method: Method, body: serde_json::Value) -> Self {
+        Command {
+            address: address.to_owned(),
+            method,
+            body,
+        }
+    }
+
+    /// Command setting the state of a light.
+    ///
+    /// Only the modified attributes are sent if the state was fetched from a light.
+    /// Fails if the client doesn't know its username.
+    pub fn light_state<C: HTTPClient>(
+        http_client: &C,
+        light: &LightId,
+        state: &LightState,
+    ) -> Res<Self> {
+        let address = format!("/api/{}/lights/{}/state", username(http_client)?, light);
+        Ok(Self::new(&address, Method::Put, state_body(state)?))
+    }
+
+    /// Command setting the action of a group.
+    pub fn group_action<C: HTTPClient>(
+        http_client: &C,
+        group: &GroupId,
+        action: &GroupAction,
+    ) -> Res<Self> {
+        let address = format!("/api/{}/groups/{}/action", username(http_client)?, group);
+        Ok(Self::new(&address, Method::Put, state_body(action)?))
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    pub fn body(&self) -> &serde_json::Value {
+        &self.body
+    }
+}
+
+fn username<C: HTTPClient>(http_client: &C) -> Res<&str> {
+    http_client
+        .username()
+        .ok_or_else(|| "the client has no username, use Command::new with the full address".into())
+}
+
+fn state_body(state: &LightState) -> Res<serde_json::Value> {
+    if state.is_changed() {
+        state.changes()
+    } else {
+        Ok(serde_json::to_value(state)?)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Schedule<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<ScheduleId>,
+    name: String,
+    #[serde(default)]
+    description: String,
+    command: Command,
+    localtime: TimePattern,
+    #[serde(with = "crate::timestamp", default)]
+    created: Option<NaiveDateTime>,
+    status: ScheduleStatus,
+    #[serde(default)]
+    autodelete: Option<bool>,
+    #[serde(with = "crate::timestamp", default)]
+    starttime: Option<NaiveDateTime>,
+    #[serde(default)]
+    recycle: Option<bool>,
+}
+
+/// API for operations on the schedules.
+impl<'a, C: HTTPClient + Default> Schedule<'a, C> {
+    pub fn get_schedules(http_client: &'a C) -> Res<BTreeMap<ScheduleId, Self>> {
+        let resp = http_client.get("schedules")?;
+        let mut schedules: BTreeMap<ScheduleId, Self> = serde_json::from_str(&resp)?;
+        for (id, schedule) in schedules.iter_mut() {
+            schedule.id = Some(id.clone());
+            schedule.client = Some(http_client);
+        }
+        Ok(schedules)
+    }
+
+    pub fn get_schedule(http_client: &'a C, id: &ScheduleId) -> Res<Self> {
+        let response = http_client.get(&format!("schedules/{}", id))?;
+        let mut schedule: Self = serde_json::from_str(&response)?;
+        schedule.id = Some(id.clone());
+        schedule.client = Some(http_client);
+        Ok(schedule)
+    }
+
+    /// Create a schedule.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hue_core::lights::*;
+    /// use hue_core::schedules::*;
+    /// use hue_core::*;
+    ///
+    /// fn example<C: HTTPClient + Default>(client: &C) {
+    ///     let state = LightStateBuilder::default().on(Some(true)).build().unwrap();
+    ///     let command = Command::light_state(client, &LightId::from(1), &state).unwrap();
+    ///     let time: TimePattern = "W124/T07:00:00A00:10:00".parse().unwrap();
+    ///     Schedule::create_schedule(client, "Wake up".to_owned(), command, time).unwrap();
+    /// }
+    /// ```
+    pub fn create_schedule(
+        http_client: &'a C,
+        name: String,
+        command: Command,
+        localtime: TimePattern,
+    ) -> Res<Self> {
+        let body = json!({
+            "name": name,
+            "command": command,
+            "localtime": localtime,
+        });
+        capabilities::ensure_available(http_client, &[("schedules", 1)])?;
+        let response = http_client.post("schedules", body.to_string())?;
+        let id = created_id(&response)?;
+        Self::get_schedule(http_client, &id.parse()?)
+    }
+
+    pub fn delete_schedule(http_client: &'a C, id: &ScheduleId) -> Res<()> {
+        let response = http_client.delete(&format!("schedules/{}", id))?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    /// Send the writable attributes and get the updated schedule.
+    pub fn update(self) -> Res<Self> {
+        let mut attributes = json!({
+            "name": self.name,
+            "description": self.description,
+            "command": self.command,
+            "localtime": self.localtime,
+            "status": self.status,
+        });
+        if let Some(autodelete) = self.autodelete {
+            attributes["autodelete"] = json!(autodelete);
+        }
+        let response = self
+            .client()
+            .put(&format!("schedules/{}", self.id()), attributes.to_string())?;
+        response_success(&response)?;
+        Self::get_schedule(self.client.unwrap(), self.id())
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        let body = json!({ "name": name });
+        let response = self
+            .client()
+            .put(&format!("schedules/{}", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) -> Res<&mut Self> {
+        let status = if enabled {
+            ScheduleStatus::Enabled
+        } else {
+            ScheduleStatus::Disabled
+        };
+        let body = json!({ "status": status });
+        let response = self
+            .client()
+            .put(&format!("schedules/{}", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.status = status;
+        Ok(self)
+    }
+
+    pub fn delete(self) -> Res<()> {
+        Self::delete_schedule(self.client.unwrap(), self.id())
+    }
+
+    /// Set the description, sent with `update`.
+    pub fn set_description(&mut self, description: &str) -> &mut Self {
+        self.description = description.to_owned();
+        self
+    }
+
+    /// Set the command, sent with `update`.
+    pub fn set_command(&mut self, command: Command) -> &mut Self {
+        self.command = command;
+        self
+    }
+
+    /// Set the time, sent with `update`.
+    pub fn set_localtime(&mut self, localtime: TimePattern) -> &mut Self {
+        self.localtime = localtime;
+        self
+    }
+
+    /// Set whether a one time schedule is deleted after it triggered, sent with `update`.
+    pub fn set_autodelete(&mut self, autodelete: bool) -> &mut Self {
+        self.autodelete = Some(autodelete);
+        self
+    }
+
+    pub fn id(&self) -> &ScheduleId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn command(&self) -> &Command {
+        &self.command
+    }
+
+    pub fn localtime(&self) -> &TimePattern {
+        &self.localtime
+    }
+
+    pub fn status(&self) -> &ScheduleStatus {
+        &self.status
+    }
+
+    pub fn autodelete(&self) -> Option<bool> {
+        self.autodelete
+    }
+
+    pub fn created(&self) -> Option<NaiveDateTime> {
+        self.created
+    }
+
+    /// Start of the timer of a timer schedule.
+    pub fn starttime(&self) -> Option<NaiveDateTime> {
+        self.starttime
+    }
+}
+
+impl<'a, C: HTTPClient + Default> Resource<'a, C> for Schedule<'a, C> {
+    type Id = ScheduleId;
+
+    const PATH: &'static str = "schedules";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<ScheduleId, Self>> {
+        Self::get_schedules(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &ScheduleId) -> Res<Self> {
+        Self::get_schedule(http_client, id)
+    }
+
+    fn id(&self) -> ScheduleId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_schedules {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+    use crate::lights::LightStateBuilder;
+    use chrono::NaiveDate;
+
+    const SCHEDULE: &str = r#"
+        {
+            "name": "Wake up",
+            "description": "Bedroom lights on workdays",
+            "command": {
+                "address": "/api/username/groups/1/action",
+                "body": {"scene": "abc", "transitiontime": 600},
+                "method": "PUT"
+            },
+            "time": "W124/T05:00:00",
+            "localtime": "W124/T07:00:00A00:10:00",
+            "created": "2019-06-01T20:11:42",
+            "status": "enabled",
+            "recycle": false
+        }"#;
+
+    #[test]
+    fn parse_time_patterns() {
+        let patterns = [
+            "2026-10-18T07:00:00",
+            "2026-10-18T07:00:00A00:30:00",
+            "W127/T07:00:00",
+            "W003/T09:30:00A00:15:00",
+            "PT00:10:00",
+            "PT00:10:00A00:01:00",
+            "R05/PT00:00:30",
+            "R/PT01:00:00",
+            "R10/PT00:05:00A00:00:30",
+        ];
+        for pattern in patterns.iter() {
+            let parsed: TimePattern = pattern.parse().unwrap();
+            assert_eq!(&parsed.to_string(), pattern);
+        }
+
+        let pattern: TimePattern = "2026-10-18T07:00:00A00:30:00".parse().unwrap();
+        assert_eq!(
+            pattern,
+            TimePattern::absolute(
+                NaiveDate::from_ymd_opt(2026, 10, 18)
+                    .unwrap()
+                    .and_hms_opt(7, 0, 0)
+                    .unwrap()
+            )
+            .randomized(Duration::from_secs(30 * 60))
+            .unwrap()
+        );
+        let pattern: TimePattern = "R05/PT00:00:30".parse().unwrap();
+        assert_eq!(
+            pattern,
+            TimePattern::recurring_timer(Some(5), Duration::from_secs(30)).unwrap()
+        );
+        let pattern: TimePattern = "W124/T07:00:00".parse().unwrap();
+        assert_eq!(
+            pattern,
+            TimePattern::recurring(
+                Weekdays::WORKDAYS,
+                NaiveTime::from_hms_opt(7, 0, 0).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_invalid_time_patterns() {
+        let patterns = [
+            "",
+            "2026-10-18",
+            "2026-10-18T25:00:00",
+            "W128/T07:00:00",
+            "W000/T07:00:00",
+            "W127T07:00:00",
+            "PT00:10",
+            "R05PT00:00:30",
+            "PT00:10:00A10",
+            "R00/PT00:00:30",
+            "R100/PT00:00:30",
+        ];
+        for pattern in patterns.iter() {
+            assert!(pattern.parse::<TimePattern>().is_err(), "{}", pattern);
+        }
+        assert!(TimePattern::timer(Duration::from_secs(24 * 3600)).is_err());
+        assert!(TimePattern::recurring_timer(Some(0), Duration::from_secs(1)).is_err());
+        assert!(TimePattern::recurring_timer(Some(100), Duration::from_secs(1)).is_err());
+        assert!(TimePattern::recurring_timer(Some(99), Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn weekdays() {
+        let days = Weekdays::from(Weekday::Mon) | Weekdays::from(Weekday::Sun);
+        assert_eq!(days.bits(), 65);
+        assert!(days.contains(Weekdays::MONDAY));
+        assert!(!days.contains(Weekdays::WEEKEND));
+        assert!(Weekdays::ALL.contains(Weekdays::WORKDAYS | Weekdays::WEEKEND));
+    }
+
+    #[test]
+    fn get_schedule_ok() {
+        let client = HTTPClientMock {
+            return_string: Some(SCHEDULE.to_owned()),
+            ..Default::default()
+        };
+        let schedule = Schedule::get_schedule(&client, &1.into()).unwrap();
+        assert_eq!(schedule.status(), &ScheduleStatus::Enabled);
+        assert_eq!(
+            schedule.localtime().random(),
+            Some(Duration::from_secs(10 * 60))
+        );
+        assert_eq!(schedule.command().method(), &Method::Put);
+    }
+
+    #[test]
+    fn get_schedule_legacy_time() {
+        let client = HTTPClientMock {
+            return_string: Some(SCHEDULE.replace("W124/T07:00:00A00:10:00", "2026-10-18T07:00")),
+            ..Default::default()
+        };
+        let schedule = Schedule::get_schedule(&client, &1.into()).unwrap();
+        assert_eq!(
+            schedule.localtime(),
+            &TimePattern::Other("2026-10-18T07:00".to_owned())
+        );
+        assert_eq!(schedule.localtime().to_string(), "2026-10-18T07:00");
+        assert!(schedule
+            .localtime()
+            .clone()
+            .randomized(Duration::from_secs(60))
+            .is_err());
+    }
+
+    #[test]
+    fn command_without_username() {
+        let state = LightStateBuilder::default().on(true).build().unwrap();
+        assert!(Command::light_state(&HTTPClientMock::default(), &2.into(), &state).is_err());
+    }
+
+    #[test]
+    fn create_schedule_ok() {
+        let client = HTTPClientMock {
+            body: Some(r#"[{"success":{"id":"1"}}]"#.to_owned()),
+            return_string: Some(SCHEDULE.to_owned()),
+            username: Some("username".to_owned()),
+            ..Default::default()
+        }
+        .with_capabilities();
+        let state = LightStateBuilder::default()
+            .on(Some(true))
+            .bri(Some(254))
+            .build()
+            .unwrap();
+        let command = Command::light_state(&client, &2.into(), &state).unwrap();
+        let time = TimePattern::timer(Duration::from_secs(600)).unwrap();
+        let mut schedule =
+            Schedule::create_schedule(&client, "Timer".to_owned(), command, time).unwrap();
+        schedule.set_enabled(false).unwrap();
+
+        let requests = client.requests.borrow();
+        let bodies: Vec<&String> = requests
+            .iter()
+            .filter(|(method, _, _)| method != "GET")
+            .map(|(_, _, body)| body)
+            .collect();
+        assert_eq!(
+            bodies,
+            vec![
+                r#"{"command":{"address":"/api/username/lights/2/state","body":{"bri":254,"on":true},"method":"PUT"},"localtime":"PT00:10:00","name":"Timer"}"#,
+                r#"{"status":"disabled"}"#,
+            ]
+        );
+    }
+}
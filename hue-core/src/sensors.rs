@@ -0,0 +1,588 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::BTreeMap;
+
+use super::lights::Alert;
+use super::resource::Resource;
+use super::*;
+
+string_id! {
+    /// ID of a sensor, e.g. `2`.
+    pub struct SensorId;
+}
+
+string_enum! {
+    /// Kind of a sensor, determines the attributes of its state.
+    pub enum SensorType {
+        /// Motion sensor.
+        ZLLPresence => "ZLLPresence",
+        /// Temperature part of a motion sensor.
+        ZLLTemperature => "ZLLTemperature",
+        /// Light level part of a motion sensor.
+        ZLLLightLevel => "ZLLLightLevel",
+        /// Dimmer switch.
+        ZLLSwitch => "ZLLSwitch",
+        /// Tap switch.
+        ZGPSwitch => "ZGPSwitch",
+        /// Bridge's virtual sensor of sunrise and sunset.
+        Daylight => "Daylight",
+        CLIPGenericStatus => "CLIPGenericStatus",
+        CLIPGenericFlag => "CLIPGenericFlag",
+        CLIPPresence => "CLIPPresence",
+        CLIPTemperature => "CLIPTemperature",
+        CLIPLightLevel => "CLIPLightLevel",
+        CLIPSwitch => "CLIPSwitch",
+        CLIPOpenClose => "CLIPOpenClose",
+        CLIPHumidity => "CLIPHumidity",
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PresenceState {
+    presence: Option<bool>,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl PresenceState {
+    pub fn presence(&self) -> bool {
+        self.presence.unwrap_or(false)
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TemperatureState {
+    temperature: Option<i32>, // 0.01 degrees Celsius
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl TemperatureState {
+    /// Temperature in degrees Celsius.
+    pub fn celsius(&self) -> Option<f32> {
+        self.temperature.map(|t| t as f32 / 100.0)
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LightLevelState {
+    lightlevel: Option<u32>, // 10000 * log10(lux) + 1
+    dark: Option<bool>,
+    daylight: Option<bool>,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl LightLevelState {
+    pub fn lightlevel(&self) -> Option<u32> {
+        self.lightlevel
+    }
+    /// Illuminance in lux.
+    pub fn lux(&self) -> Option<f32> {
+        self.lightlevel
+            .map(|level| 10f32.powf((level as f32 - 1.0) / 10000.0))
+    }
+    /// Light level is below the dark threshold.
+    pub fn dark(&self) -> bool {
+        self.dark.unwrap_or(false)
+    }
+    /// Light level is above the dark threshold plus offset.
+    pub fn daylight(&self) -> bool {
+        self.daylight.unwrap_or(false)
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SwitchState {
+    buttonevent: Option<u32>,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl SwitchState {
+    /// Last button event, e.g. 1002 for a short release of the first button.
+    pub fn buttonevent(&self) -> Option<u32> {
+        self.buttonevent
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DaylightState {
+    daylight: Option<bool>,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl DaylightState {
+    /// Whether the sun is up, `None` if the location is not configured.
+    pub fn daylight(&self) -> Option<bool> {
+        self.daylight
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenericStatusState {
+    status: i32,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl GenericStatusState {
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenericFlagState {
+    flag: bool,
+    #[serde(with = "crate::timestamp", default)]
+    lastupdated: Option<NaiveDateTime>,
+}
+
+impl GenericFlagState {
+    pub fn flag(&self) -> bool {
+        self.flag
+    }
+    pub fn lastupdated(&self) -> Option<NaiveDateTime> {
+        self.lastupdated
+    }
+}
+
+/// State of a sensor typed by the sensor type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SensorState {
+    Presence(PresenceState),
+    Temperature(TemperatureState),
+    LightLevel(LightLevelState),
+    Switch(SwitchState),
+    Daylight(DaylightState),
+    GenericStatus(GenericStatusState),
+    GenericFlag(GenericFlagState),
+    /// State of a sensor type without a typed state.
+    Other(serde_json::Value),
+}
+
+#[derive(Builder, Serialize, Deserialize, Default, Debug, Clone)]
+#[builder(default)]
+#[builder(setter(into))]
+pub struct SensorConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on: Option<bool>,
+    #[serde(skip_serializing)]
+    reachable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    battery: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alert: Option<Alert>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ledindication: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usertest: Option<bool>,
+    // presence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensitivity: Option<u8>,
+    #[serde(skip_serializing)]
+    sensitivitymax: Option<u8>,
+    // light level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tholddark: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tholdoffset: Option<u32>,
+    // daylight
+    #[serde(skip_serializing_if = "Option::is_none")]
+    long: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lat: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunriseoffset: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunsetoffset: Option<i8>,
+    #[serde(skip_serializing)]
+    configured: Option<bool>,
+}
+
+impl SensorConfig {
+    pub fn on(&self) -> bool {
+        self.on.unwrap_or(false)
+    }
+    pub fn reachable(&self) -> Option<bool> {
+        self.reachable
+    }
+    /// Battery level in percent.
+    pub fn battery(&self) -> Option<u8> {
+        self.battery
+    }
+    pub fn sensitivity(&self) -> Option<u8> {
+        self.sensitivity
+    }
+    pub fn tholddark(&self) -> Option<u32> {
+        self.tholddark
+    }
+    pub fn configured(&self) -> Option<bool> {
+        self.configured
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Sensor<'a, C: HTTPClient + Default> {
+    #[serde(skip)]
+    client: Option<&'a C>,
+    #[serde(skip)]
+    id: Option<SensorId>,
+    name: String,
+    #[serde(rename = "type")]
+    ty: SensorType,
+    modelid: String,
+    manufacturername: String,
+    #[serde(default)]
+    swversion: Option<String>,
+    #[serde(default)]
+    uniqueid: Option<String>,
+    #[serde(default)]
+    recycle: Option<bool>,
+    state: serde_json::Value,
+    config: SensorConfig,
+}
+
+/// API for operations on the sensors.
+impl<'a, C: HTTPClient + Default> Sensor<'a, C> {
+    pub fn get_sensors(http_client: &'a C) -> Res<BTreeMap<SensorId, Self>> {
+        let resp = http_client.get("sensors")?;
+        let mut sensors: BTreeMap<SensorId, Self> = serde_json::from_str(&resp)?;
+        for (id, sensor) in sensors.iter_mut() {
+            sensor.id = Some(id.clone());
+            sensor.client = Some(http_client);
+        }
+        Ok(sensors)
+    }
+
+    pub fn get_sensor(http_client: &'a C, id: &SensorId) -> Res<Self> {
+        let response = http_client.get(&format!("sensors/{}", id))?;
+        let mut sensor: Self = serde_json::from_str(&response)?;
+        sensor.id = Some(id.clone());
+        sensor.client = Some(http_client);
+        Ok(sensor)
+    }
+
+    pub fn delete_sensor(http_client: &'a C, id: &SensorId) -> Res<()> {
+        let response = http_client.delete(&format!("sensors/{}", id))?;
+        response_success(&response)?;
+        Ok(())
+    }
+
+    pub fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        let body = json!({ "name": name });
+        let response = self
+            .client()
+            .put(&format!("sensors/{}", self.id()), body.to_string())?;
+        response_success(&response)?;
+        self.name = name.to_owned();
+        Ok(self)
+    }
+
+    /// Send the config attributes that are set and get the updated sensor.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use hue_core::sensors::*;
+    /// use hue_core::*;
+    ///
+    /// fn example<C: HTTPClient + Default>(client: &C) {
+    ///     let sensor = Sensor::get_sensor(client, &SensorId::from(2)).unwrap();
+    ///     let config = SensorConfigBuilder::default()
+    ///         .sensitivity(Some(2))
+    ///         .build()
+    ///         .unwrap();
+    ///     sensor.update_config(&config).unwrap();
+    /// }
+    /// ```
+    pub fn update_config(self, config: &SensorConfig) -> Res<Self> {
+        let body = serde_json::to_string(config)?;
+        let response = self
+            .client()
+            .put(&format!("sensors/{}/config", self.id()), body)?;
+        response_success(&response)?;
+        Self::get_sensor(self.client.unwrap(), self.id())
+    }
+
+    /// Send the name and get the updated sensor.
+    pub fn update(self) -> Res<Self> {
+        let attributes = json!({ "name": self.name });
+        let response = self
+            .client()
+            .put(&format!("sensors/{}", self.id()), attributes.to_string())?;
+        response_success(&response)?;
+        Self::get_sensor(self.client.unwrap(), self.id())
+    }
+
+    pub fn delete(self) -> Res<()> {
+        Self::delete_sensor(self.client.unwrap(), self.id())
+    }
+
+    /// Get the state typed by the sensor type.
+    pub fn state(&self) -> Res<SensorState> {
+        let state = self.state.clone();
+        Ok(match self.ty {
+            SensorType::ZLLPresence | SensorType::CLIPPresence => {
+                SensorState::Presence(serde_json::from_value(state)?)
+            }
+            SensorType::ZLLTemperature | SensorType::CLIPTemperature => {
+                SensorState::Temperature(serde_json::from_value(state)?)
+            }
+            SensorType::ZLLLightLevel | SensorType::CLIPLightLevel => {
+                SensorState::LightLevel(serde_json::from_value(state)?)
+            }
+            SensorType::ZLLSwitch | SensorType::ZGPSwitch | SensorType::CLIPSwitch => {
+                SensorState::Switch(serde_json::from_value(state)?)
+            }
+            SensorType::Daylight => SensorState::Daylight(serde_json::from_value(state)?),
+            SensorType::CLIPGenericStatus => {
+                SensorState::GenericStatus(serde_json::from_value(state)?)
+            }
+            SensorType::CLIPGenericFlag => SensorState::GenericFlag(serde_json::from_value(state)?),
+            _ => SensorState::Other(state),
+        })
+    }
+
+    pub fn id(&self) -> &SensorId {
+        self.id.as_ref().unwrap()
+    }
+
+    pub fn client(&self) -> &C {
+        self.client.unwrap()
+    }
+
+    pub fn ty(&self) -> &SensorType {
+        &self.ty
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn modelid(&self) -> &str {
+        &self.modelid
+    }
+
+    pub fn config(&self) -> &SensorConfig {
+        &self.config
+    }
+}
+
+impl<'a, C: HTTPClient + Default> Resource<'a, C> for Sensor<'a, C> {
+    type Id = SensorId;
+
+    const PATH: &'static str = "sensors";
+
+    fn list(http_client: &'a C) -> Res<BTreeMap<SensorId, Self>> {
+        Self::get_sensors(http_client)
+    }
+
+    fn get(http_client: &'a C, id: &SensorId) -> Res<Self> {
+        Self::get_sensor(http_client, id)
+    }
+
+    fn id(&self) -> SensorId {
+        self.id().clone()
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn rename(&mut self, name: &str) -> Res<&mut Self> {
+        self.rename(name)
+    }
+
+    fn update(self) -> Res<Self> {
+        self.update()
+    }
+
+    fn delete(self) -> Res<()> {
+        self.delete()
+    }
+}
+
+#[cfg(test)]
+mod tests_sensors {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+
+    const SENSORS: &str = r#"
+        {
+            "1": {
+                "state": {"daylight": true, "lastupdated": "2019-06-01T05:12:00"},
+                "config": {"on": true, "configured": true, "sunriseoffset": 30, "sunsetoffset": -30},
+                "name": "Daylight",
+                "type": "Daylight",
+                "modelid": "PHDL00",
+                "manufacturername": "Philips",
+                "swversion": "1.0"
+            },
+            "2": {
+                "state": {"presence": false, "lastupdated": "2019-06-02T19:24:41"},
+                "swupdate": {"state": "noupdates", "lastinstall": "2019-03-16T21:16:40"},
+                "config": {"on": true, "battery": 90, "reachable": true, "alert": "none", "ledindication": false, "usertest": false, "sensitivity": 2, "sensitivitymax": 2, "pending": []},
+                "name": "Hall sensor",
+                "type": "ZLLPresence",
+                "modelid": "SML001",
+                "manufacturername": "Philips",
+                "productname": "Hue motion sensor",
+                "swversion": "6.1.1.27575",
+                "uniqueid": "00:17:88:01:02:00:b5:d7-02-0406",
+                "capabilities": {"certified": true, "primary": true}
+            },
+            "3": {
+                "state": {"temperature": 2153, "lastupdated": "2019-06-02T19:24:41"},
+                "config": {"on": true, "battery": 90, "reachable": true},
+                "name": "Hue temperature sensor 1",
+                "type": "ZLLTemperature",
+                "modelid": "SML001",
+                "manufacturername": "Philips"
+            },
+            "4": {
+                "state": {"lightlevel": 14355, "dark": true, "daylight": false, "lastupdated": "2019-06-02T19:24:41"},
+                "config": {"on": true, "battery": 90, "reachable": true, "tholddark": 16000, "tholdoffset": 7000},
+                "name": "Hue ambient light sensor 1",
+                "type": "ZLLLightLevel",
+                "modelid": "SML001",
+                "manufacturername": "Philips"
+            },
+            "5": {
+                "state": {"buttonevent": 1002, "lastupdated": "2019-06-02T18:41:06"},
+                "config": {"on": true, "battery": 100, "reachable": true, "pending": []},
+                "name": "Dimmer switch",
+                "type": "ZLLSwitch",
+                "modelid": "RWL021",
+                "manufacturername": "Philips"
+            },
+            "6": {
+                "state": {"status": 1, "lastupdated": "2019-06-02T18:41:06"},
+                "config": {"on": true, "reachable": true},
+                "name": "Away status",
+                "type": "CLIPGenericStatus",
+                "modelid": "AWAY",
+                "manufacturername": "hue-rs",
+                "swversion": "1.0",
+                "uniqueid": "away",
+                "recycle": true
+            },
+            "7": {
+                "state": {"flag": true, "lastupdated": "none"},
+                "config": {"on": true, "reachable": true},
+                "name": "Flag",
+                "type": "CLIPGenericFlag",
+                "modelid": "FLAG",
+                "manufacturername": "hue-rs"
+            },
+            "8": {
+                "state": {"open": false, "lastupdated": "none"},
+                "config": {"on": true, "reachable": true},
+                "name": "Door",
+                "type": "CLIPOpenClose",
+                "modelid": "DOOR",
+                "manufacturername": "hue-rs"
+            }
+        }"#;
+
+    #[test]
+    fn get_sensors_ok() {
+        let client = HTTPClientMock {
+            return_string: Some(SENSORS.to_owned()),
+            ..Default::default()
+        };
+        let sensors = Sensor::get_sensors(&client).unwrap();
+        assert_eq!(sensors.len(), 8);
+
+        let states: Vec<SensorState> = sensors
+            .values()
+            .map(|sensor| sensor.state().unwrap())
+            .collect();
+        match &states[..] {
+            [SensorState::Daylight(daylight), SensorState::Presence(presence), SensorState::Temperature(temperature), SensorState::LightLevel(light_level), SensorState::Switch(switch), SensorState::GenericStatus(status), SensorState::GenericFlag(flag), SensorState::Other(_)] =>
+            {
+                assert_eq!(daylight.daylight(), Some(true));
+                assert!(!presence.presence());
+                assert_eq!(temperature.celsius(), Some(21.53));
+                assert!(light_level.dark());
+                assert!((light_level.lux().unwrap() - 27.2).abs() < 0.1);
+                assert_eq!(switch.buttonevent(), Some(1002));
+                assert_eq!(status.status(), 1);
+                assert!(flag.flag());
+            }
+            _ => panic!("unexpected states {:?}", states),
+        }
+
+        let motion = &sensors["2"];
+        assert_eq!(motion.ty(), &SensorType::ZLLPresence);
+        assert_eq!(motion.config().battery(), Some(90));
+        assert_eq!(motion.config().sensitivity(), Some(2));
+    }
+
+    #[test]
+    fn update_sensor() {
+        let sensors: serde_json::Value = serde_json::from_str(SENSORS).unwrap();
+        let client = HTTPClientMock {
+            body: Some(r#"[{"success":{}}]"#.to_owned()),
+            return_string: Some(sensors["2"].to_string()),
+            ..Default::default()
+        };
+        let mut sensor = Sensor::get_sensor(&client, &2.into()).unwrap();
+        sensor.rename("Hallway sensor").unwrap();
+        assert_eq!(sensor.name(), "Hallway sensor");
+
+        let config = SensorConfigBuilder::default()
+            .sensitivity(Some(1))
+            .ledindication(Some(true))
+            .build()
+            .unwrap();
+        let sensor = sensor.update_config(&config).unwrap();
+        sensor.delete().unwrap();
+
+        let requests: Vec<(String, String, String)> = client
+            .requests
+            .borrow()
+            .iter()
+            .filter(|(method, _, _)| method != "GET")
+            .cloned()
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    "PUT".to_owned(),
+                    "sensors/2".to_owned(),
+                    r#"{"name":"Hallway sensor"}"#.to_owned()
+                ),
+                (
+                    "PUT".to_owned(),
+                    "sensors/2/config".to_owned(),
+                    r#"{"ledindication":true,"sensitivity":1}"#.to_owned()
+                ),
+                ("DELETE".to_owned(), "sensors/2".to_owned(), "".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Timestamps of the bridge, `YYYY-MM-DDTHH:MM:SS` or `none`.
+//!
+//! Use with `#[serde(with = "crate::timestamp", default)]` on an
+//! `Option<NaiveDateTime>` field, `none` is `None`.
+
+use chrono::NaiveDateTime;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::Deserialize;
+
+/// Format of the timestamps.
+pub const FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Parse a timestamp, `None` for `none` or an invalid timestamp.
+pub fn parse(timestamp: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(timestamp, FORMAT).ok()
+}
+
+/// Format a timestamp as the bridge does, `none` for `None`.
+pub fn format(timestamp: Option<NaiveDateTime>) -> String {
+    match timestamp {
+        Some(time) => time.format(FORMAT).to_string(),
+        None => "none".to_owned(),
+    }
+}
+
+pub fn serialize<S: Serializer>(
+    timestamp: &Option<NaiveDateTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(*timestamp))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(timestamp) if timestamp != "none" && !timestamp.is_empty() => {
+            NaiveDateTime::parse_from_str(&timestamp, FORMAT)
+                .map(Some)
+                .map_err(|e| de::Error::custom(format!("invalid timestamp {}: {}", timestamp, e)))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests_timestamp {
+
+    use super::*;
+    use chrono::NaiveDate;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Resource {
+        #[serde(with = "crate::timestamp", default)]
+        lastupdated: Option<NaiveDateTime>,
+    }
+
+    #[test]
+    fn deserialize_timestamps() {
+        let time = NaiveDate::from_ymd_opt(2019, 6, 2)
+            .unwrap()
+            .and_hms_opt(17, 24, 41)
+            .unwrap();
+        let resources = [
+            (r#"{"lastupdated": "2019-06-02T17:24:41"}"#, Some(time)),
+            (r#"{"lastupdated": "none"}"#, None),
+            (r#"{"lastupdated": null}"#, None),
+            ("{}", None),
+        ];
+        for (json, lastupdated) in resources.iter() {
+            let resource: Resource = serde_json::from_str(json).unwrap();
+            assert_eq!(resource.lastupdated, *lastupdated);
+        }
+        assert!(serde_json::from_str::<Resource>(r#"{"lastupdated": "yesterday"}"#).is_err());
+    }
+
+    #[test]
+    fn serialize_timestamps() {
+        let resource = Resource { lastupdated: None };
+        assert_eq!(
+            serde_json::to_string(&resource).unwrap(),
+            r#"{"lastupdated":"none"}"#
+        );
+        let resource = Resource {
+            lastupdated: parse("2019-06-02T17:24:41"),
+        };
+        assert_eq!(
+            serde_json::to_string(&resource).unwrap(),
+            r#"{"lastupdated":"2019-06-02T17:24:41"}"#
+        );
+    }
+}
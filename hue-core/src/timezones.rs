@@ -0,0 +1,159 @@
+use chrono::{DateTime, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json;
+
+use super::schedules::TimePattern;
+use super::*;
+
+/// Get the timezones the bridge can be configured with.
+pub fn get_timezones<C: HTTPClient>(http_client: &C) -> Res<Vec<String>> {
+    let response = http_client.get("info/timezones")?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+/// Parse a timestamp of the bridge in UTC, `None` for `none` or an invalid timestamp.
+pub fn parse_utc(timestamp: &str) -> Option<DateTime<Utc>> {
+    timestamp::parse(timestamp).map(|time| time.and_utc())
+}
+
+/// Current time of the bridge and its timezone, for conversions to and from
+/// the bridge local time used by schedules and rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeTime {
+    utc: DateTime<Utc>,
+    localtime: NaiveDateTime,
+    timezone: Tz,
+}
+
+impl BridgeTime {
+    /// Create from the `UTC`, `localtime` and `timezone` of the bridge configuration.
+    pub fn new(utc: NaiveDateTime, localtime: NaiveDateTime, timezone: &str) -> Res<Self> {
+        Ok(BridgeTime {
+            utc: utc.and_utc(),
+            localtime,
+            timezone: timezone
+                .parse()
+                .map_err(|e| format!("invalid timezone {}: {}", timezone, e))?,
+        })
+    }
+
+    pub fn utc(&self) -> &DateTime<Utc> {
+        &self.utc
+    }
+
+    pub fn localtime(&self) -> &NaiveDateTime {
+        &self.localtime
+    }
+
+    pub fn timezone(&self) -> &Tz {
+        &self.timezone
+    }
+
+    /// Current time of the bridge in its timezone.
+    pub fn now(&self) -> DateTime<Tz> {
+        self.utc.with_timezone(&self.timezone)
+    }
+
+    /// Convert a time to the bridge timezone.
+    pub fn to_local<T: TimeZone>(&self, time: &DateTime<T>) -> DateTime<Tz> {
+        time.with_timezone(&self.timezone)
+    }
+
+    /// Get the time of a bridge local time.
+    ///
+    /// The earlier time is used for a local time that occurs twice when the
+    /// clocks go back, a local time skipped when the clocks go forward is an
+    /// error.
+    pub fn from_local(&self, local: &NaiveDateTime) -> Res<DateTime<Tz>> {
+        match self.timezone.from_local_datetime(local) {
+            LocalResult::Single(time) => Ok(time),
+            LocalResult::Ambiguous(earliest, _) => Ok(earliest),
+            LocalResult::None => {
+                Err(format!("{} doesn't exist in {}", local, self.timezone).into())
+            }
+        }
+    }
+
+    /// Time pattern triggering once at a time.
+    pub fn pattern_at<T: TimeZone>(&self, time: &DateTime<T>) -> TimePattern {
+        TimePattern::absolute(self.to_local(time).naive_local())
+    }
+
+    /// Time an absolute pattern triggers at, `None` for the other patterns.
+    pub fn pattern_time(&self, pattern: &TimePattern) -> Res<Option<DateTime<Tz>>> {
+        match pattern {
+            TimePattern::Absolute { time, .. } => Ok(Some(self.from_local(time)?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_timezones {
+
+    use super::test_common::HTTPClientMock;
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn bridge_time() -> BridgeTime {
+        BridgeTime::new(
+            timestamp::parse("2019-06-02T17:24:41").unwrap(),
+            timestamp::parse("2019-06-02T19:24:41").unwrap(),
+            "Europe/Ljubljana",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn get_timezones_ok() {
+        let client = HTTPClientMock {
+            return_string: Some(r#"["CET", "Europe/Ljubljana", "UTC"]"#.to_owned()),
+            ..Default::default()
+        };
+        let timezones = get_timezones(&client).unwrap();
+        assert_eq!(timezones.len(), 3);
+        assert_eq!(client.requests.borrow()[0].1, "info/timezones");
+    }
+
+    #[test]
+    fn parse_timestamps() {
+        assert_eq!(
+            parse_utc("2019-06-02T17:24:41").unwrap().to_rfc3339(),
+            "2019-06-02T17:24:41+00:00"
+        );
+        assert_eq!(parse_utc("none"), None);
+        let time = timestamp::parse("2019-06-02T17:24:41").unwrap();
+        assert!(BridgeTime::new(time, time, "Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn local_time() {
+        let time = bridge_time();
+        assert_eq!(time.now().naive_local(), *time.localtime());
+
+        let utc = parse_utc("2026-10-18T05:00:00").unwrap();
+        let pattern = time.pattern_at(&utc);
+        assert_eq!(pattern.to_string(), "2026-10-18T07:00:00");
+        assert_eq!(time.pattern_time(&pattern).unwrap().unwrap(), utc);
+        assert_eq!(
+            time.pattern_time(&"PT00:10:00".parse().unwrap()).unwrap(),
+            None
+        );
+
+        // clocks go back from 3:00 to 2:00
+        let local = NaiveDate::from_ymd_opt(2026, 10, 25)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        assert_eq!(
+            time.from_local(&local).unwrap().to_rfc3339(),
+            "2026-10-25T02:30:00+02:00"
+        );
+        // clocks go forward from 2:00 to 3:00
+        let local = NaiveDate::from_ymd_opt(2026, 3, 29)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        assert!(time.from_local(&local).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 extern crate hue;
+extern crate humantime;
 extern crate slog;
 extern crate slog_term;
 #[macro_use]
@@ -6,12 +7,19 @@ extern crate clap;
 
 use clap::App;
 use clap::ArgMatches;
-use serde_yaml;
+use std::fs;
+use std::io::{self, Write};
 use std::process;
+use std::time::Duration;
 
+use hue::capabilities::*;
+use hue::config::*;
+use hue::firmware::*;
 use hue::groups::*;
 use hue::lights::*;
+use hue::resource::Resource;
 use hue::scenes::*;
+use hue::sensors::*;
 use hue::*;
 
 fn main() {
@@ -25,36 +33,36 @@ fn main() {
 }
 
 fn run(matches: ArgMatches) -> Res<()> {
-    let ref client = Client::new(matches.value_of("config"));
+    let client = &Client::new(matches.value_of("config"));
 
     match matches.subcommand() {
-        ("light", Some(sub_m)) => return run_light(client, sub_m),
-        ("group", Some(sub_m)) => return run_group(client, sub_m),
-        ("scene", Some(sub_m)) => return run_scene(client, sub_m),
-        _ => return Ok(()),
+        ("light", Some(sub_m)) => run_light(client, sub_m),
+        ("group", Some(sub_m)) => run_group(client, sub_m),
+        ("scene", Some(sub_m)) => run_scene(client, sub_m),
+        ("firmware", Some(sub_m)) => run_firmware(client, sub_m),
+        ("sensor", Some(sub_m)) => run_sensor(client, sub_m),
+        ("bridge", Some(sub_m)) => run_bridge(client, sub_m),
+        ("users", Some(sub_m)) => run_users(client, sub_m),
+        _ => Ok(()),
     }
 }
 
-fn run_light(client: &Client, matches: &ArgMatches) -> Res<()> {
-    match matches.subcommand() {
-        ("list", _) => return run_light_list(client),
-        ("on", Some(sub_m)) => return run_light_power(client, sub_m, true),
-        ("off", Some(sub_m)) => return run_light_power(client, sub_m, false),
-        _ => return Ok(()),
-    }
-}
+/// Print all the resources of a type as YAML.
+fn run_list<'a, R: Resource<'a, Client>>(client: &'a Client) -> Res<()> {
+    let resources = R::list(client)?;
+    println!("{}", serde_yaml::to_string(&resources)?);
 
-fn run_light_list(client: &Client) -> Res<()> {
-    let light_list = Light::get_lights(client);
+    Ok(())
+}
 
-    if let Ok(lights) = light_list {
-        let light_list_yml = serde_yaml::to_string(&lights).unwrap();
-        println!("{}", light_list_yml);
-    } else if let Err(e) = light_list {
-        return Result::Err(e.into());
+fn run_light(client: &Client, matches: &ArgMatches) -> Res<()> {
+    match matches.subcommand() {
+        ("list", _) => run_list::<Light<_>>(client),
+        ("on", Some(sub_m)) => run_light_power(client, sub_m, true),
+        ("off", Some(sub_m)) => run_light_power(client, sub_m, false),
+        ("set", Some(sub_m)) => run_light_set(client, sub_m),
+        _ => Ok(()),
     }
-
-    Ok(())
 }
 
 fn run_light_power(client: &Client, m: &ArgMatches, power: bool) -> Res<()> {
@@ -62,7 +70,7 @@ fn run_light_power(client: &Client, m: &ArgMatches, power: bool) -> Res<()> {
         let vals: Vec<&str> = lights.collect();
 
         for val in vals {
-            let mut light = Light::get_light(client, val.parse()?)?;
+            let mut light = Light::get_light(client, &val.parse()?)?;
             light.state().set_on(power);
             light.update_state()?;
         }
@@ -77,33 +85,39 @@ fn run_light_power(client: &Client, m: &ArgMatches, power: bool) -> Res<()> {
     Ok(())
 }
 
-fn run_group(client: &Client, m: &ArgMatches) -> Res<()> {
-    match m.subcommand() {
-        ("list", _) => return run_group_list(client),
-        ("on", Some(sub_m)) => return run_group_power(client, sub_m, true),
-        ("off", Some(sub_m)) => return run_group_power(client, sub_m, false),
-        (_, _) => return Ok(()),
+fn run_light_set(client: &Client, m: &ArgMatches) -> Res<()> {
+    for val in m.values_of("light").unwrap() {
+        let mut light = Light::get_light(client, &val.parse()?)?;
+        if let Some(color) = m.value_of("color") {
+            light.set_named_color(color)?;
+        }
+        if let Some(bri) = m.value_of("bri") {
+            light.state().set_bri(bri.parse()?);
+        }
+        light.update_state()?;
     }
-}
 
-fn run_group_list(client: &Client) -> Res<()> {
-    let group_list = Group::get_groups(client);
+    Ok(())
+}
 
-    if let Ok(groups) = group_list {
-        let group_list_yml = serde_yaml::to_string(&groups).unwrap();
-        println!("{}", group_list_yml);
-    } else if let Err(e) = group_list {
-        return Result::Err(e.into());
+fn run_group(client: &Client, m: &ArgMatches) -> Res<()> {
+    match m.subcommand() {
+        ("list", _) => run_list::<Group<_>>(client),
+        ("on", Some(sub_m)) => run_group_power(client, sub_m, true),
+        ("off", Some(sub_m)) => run_group_power(client, sub_m, false),
+        ("create", Some(sub_m)) => run_group_create(client, sub_m),
+        ("add", Some(sub_m)) => run_group_members(client, sub_m, true),
+        ("remove", Some(sub_m)) => run_group_members(client, sub_m, false),
+        ("startup", Some(sub_m)) => run_group_startup(client, sub_m),
+        (_, _) => Ok(()),
     }
-
-    Ok(())
 }
 
 fn run_group_power(client: &Client, m: &ArgMatches, power: bool) -> Res<()> {
     if let Some(groups) = m.values_of("group") {
         let vals: Vec<&str> = groups.collect();
         for val in vals {
-            let mut group = Group::get_group(client, val.parse()?)?;
+            let mut group = Group::get_group(client, &val.parse()?)?;
             group.action().set_on(power);
             group.update_state()?;
         }
@@ -118,27 +132,445 @@ fn run_group_power(client: &Client, m: &ArgMatches, power: bool) -> Res<()> {
     Ok(())
 }
 
+fn run_group_create(client: &Client, m: &ArgMatches) -> Res<()> {
+    let name = m.value_of("name").unwrap().to_owned();
+    let lights = values_t!(m, "light", LightId)?;
+    let ty = m.value_of("type").map(|ty| ty.parse()).transpose()?;
+    let class = m.value_of("class").map(|class| class.parse()).transpose()?;
+
+    let group = Group::create_group(client, name, lights, ty, class)?;
+    println!("{}", group.id());
+
+    Ok(())
+}
+
+fn run_group_members(client: &Client, m: &ArgMatches, add: bool) -> Res<()> {
+    let mut group = Group::get_group(client, &value_t!(m, "group", GroupId)?)?;
+    let lights = values_t!(m, "light", LightId)?;
+    if add {
+        group.add_lights(&lights)?;
+    } else {
+        group.remove_lights(&lights)?;
+    }
+
+    Ok(())
+}
+
+fn run_group_startup(client: &Client, m: &ArgMatches) -> Res<()> {
+    let group = Group::get_group(client, &value_t!(m, "group", GroupId)?)?;
+    let mode: StartupMode = m.value_of("mode").unwrap().parse()?;
+
+    let mut custom = LightStateBuilder::default();
+    if let Some(bri) = m.value_of("bri") {
+        custom.bri(Some(bri.parse()?));
+    }
+    if let Some(ct) = m.value_of("ct") {
+        custom.ct(Some(ct.parse()?));
+    }
+    let custom = custom.build()?;
+    let custom_given = m.is_present("bri") || m.is_present("ct");
+    if custom_given && mode != StartupMode::Custom {
+        return Err("--bri and --ct are only valid with the custom mode".into());
+    }
+
+    for id in group.lights() {
+        let mut light = Light::get_light(client, id)?;
+        if light.startup().is_none() {
+            println!("light {}: no startup mode support, skipped", id);
+            continue;
+        }
+        match mode {
+            StartupMode::Custom if custom_given => light.set_startup_custom(&custom)?,
+            StartupMode::Custom => {
+                let state = light.state().clone();
+                light.set_startup_custom(&state)?
+            }
+            _ => light.set_startup(mode.clone())?,
+        };
+    }
+
+    Ok(())
+}
+
 fn run_scene(client: &Client, m: &ArgMatches) -> Res<()> {
     match m.subcommand() {
-        ("list", _) => return run_scene_list(client),
-        ("on", Some(sub_m)) => return run_scene_power(client, sub_m, true),
+        ("list", _) => run_list::<Scene<_>>(client),
+        ("on", Some(sub_m)) => run_scene_on(client, sub_m),
+        ("save", Some(sub_m)) => run_scene_save(client, sub_m),
+        ("export", Some(sub_m)) => run_scene_export(client, sub_m),
+        ("import", Some(sub_m)) => run_scene_import(client, sub_m),
+        (_, _) => Ok(()),
+    }
+}
+
+fn run_scene_on(client: &Client, m: &ArgMatches) -> Res<()> {
+    let transition = m.value_of("transition").map(|t| t.parse()).transpose()?;
+    let group: Option<GroupId> = m.value_of("group").map(|g| g.parse()).transpose()?;
+    for val in m.values_of("scene").unwrap() {
+        let scene = Scene::find_scene(client, val, group.as_ref())?;
+        scene.recall(transition)?;
+    }
+
+    Ok(())
+}
+
+fn run_scene_save(client: &Client, m: &ArgMatches) -> Res<()> {
+    let scene = match m.subcommand() {
+        ("light", Some(sub_m)) => Scene::save_light_scene(
+            client,
+            sub_m.value_of("name").unwrap().to_owned(),
+            values_t!(sub_m, "light", LightId)?,
+            sub_m.is_present("overwrite"),
+        )?,
+        ("group", Some(sub_m)) => Scene::save_group_scene(
+            client,
+            sub_m.value_of("name").unwrap().to_owned(),
+            &value_t!(sub_m, "group", GroupId)?,
+            sub_m.is_present("overwrite"),
+        )?,
         (_, _) => return Ok(()),
+    };
+    println!("{}", scene.id());
+
+    Ok(())
+}
+
+fn run_scene_export(client: &Client, m: &ArgMatches) -> Res<()> {
+    let report = export_scenes(client)?;
+    let scenes = report.scenes();
+    match m.value_of("file") {
+        Some(file) if file.ends_with(".json") => {
+            fs::write(file, serde_json::to_string_pretty(&scenes)?)?
+        }
+        Some(file) => fs::write(file, serde_yaml::to_string(&scenes)?)?,
+        None => println!("{}", serde_yaml::to_string(&scenes)?),
+    }
+    for (name, light) in report.unmatched_lights() {
+        eprintln!("scene {}: light {} not found, left out", name, light);
+    }
+
+    Ok(())
+}
+
+fn run_scene_import(client: &Client, m: &ArgMatches) -> Res<()> {
+    let file = m.value_of("file").unwrap();
+    let content = fs::read_to_string(file)?;
+    let scenes: Vec<PortableScene> = if file.ends_with(".json") {
+        serde_json::from_str(&content)?
+    } else {
+        serde_yaml::from_str(&content)?
+    };
+
+    let report = import_scenes(client, &scenes)?;
+    for (name, id) in report.created() {
+        println!("created {}: {}", name, id);
+    }
+    for (name, light) in report.unmatched_lights() {
+        println!("scene {}: light {} not matched", name, light);
+    }
+    for (name, light, error) in report.failed_lights() {
+        println!("scene {}: light {} not set: {}", name, light, error);
+    }
+    for (name, group) in report.unmatched_groups() {
+        println!("scene {}: group {} not matched, scene skipped", name, group);
+    }
+
+    Ok(())
+}
+
+fn run_firmware(client: &Client, m: &ArgMatches) -> Res<()> {
+    match m.subcommand() {
+        ("status", _) => run_firmware_status(client),
+        ("check", Some(sub_m)) => run_firmware_check(client, sub_m),
+        ("install", Some(sub_m)) => run_firmware_install(client, sub_m),
+        (_, _) => Ok(()),
     }
 }
 
-fn run_scene_list(client: &Client) -> Res<()> {
-    let scene_list = Scene::get_scenes(client);
+fn run_firmware_status(client: &Client) -> Res<()> {
+    let firmware = Firmware::get_firmware(client)?;
+    println!("state: {}", firmware.state());
+    println!("bridge: {}", firmware.bridge().state());
+    match firmware.autoinstall().updatetime() {
+        Some(time) if firmware.autoinstall().on() => println!("autoinstall: {}", time),
+        _ => println!("autoinstall: off"),
+    }
+    for (id, light) in firmware.pending_lights()? {
+        println!(
+            "light {} ({}): {}",
+            id,
+            light.name(),
+            light.swupdate().state()
+        );
+    }
+
+    Ok(())
+}
+
+fn run_firmware_check(client: &Client, m: &ArgMatches) -> Res<()> {
+    let mut firmware = Firmware::get_firmware(client)?;
+    firmware.check_for_update()?;
+    if m.is_present("wait") {
+        let firmware = wait::wait_for_check(
+            firmware,
+            Duration::from_secs(5),
+            Duration::from_secs(10 * 60),
+            |_| {},
+        )?;
+        println!("state: {}", firmware.state());
+    }
+
+    Ok(())
+}
+
+fn run_firmware_install(client: &Client, m: &ArgMatches) -> Res<()> {
+    let mut firmware = Firmware::get_firmware(client)?;
+    firmware.install()?;
+    if m.is_present("wait") {
+        wait::wait_for_install(
+            firmware,
+            Duration::from_secs(10),
+            Duration::from_secs(60 * 60),
+            |firmware| println!("state: {}", firmware.state()),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn run_sensor(client: &Client, m: &ArgMatches) -> Res<()> {
+    match m.subcommand() {
+        ("list", _) => run_list::<Sensor<_>>(client),
+        ("show", Some(sub_m)) => run_sensor_show(client, sub_m),
+        (_, _) => Ok(()),
+    }
+}
+
+fn run_sensor_show(client: &Client, m: &ArgMatches) -> Res<()> {
+    for id in values_t!(m, "sensor", SensorId)? {
+        let sensor = Sensor::get_sensor(client, &id)?;
+        println!("{} ({}): {}", sensor.name(), id, sensor.ty());
+        match sensor.state()? {
+            SensorState::Presence(state) => println!("presence: {}", state.presence()),
+            SensorState::Temperature(state) => match state.celsius() {
+                Some(celsius) => println!("temperature: {:.1} °C", celsius),
+                None => println!("temperature: unknown"),
+            },
+            SensorState::LightLevel(state) => match state.lux() {
+                Some(lux) => println!("light level: {:.0} lx, dark: {}", lux, state.dark()),
+                None => println!("light level: unknown"),
+            },
+            SensorState::Switch(state) => match state.buttonevent() {
+                Some(event) => println!("button event: {}", event),
+                None => println!("button event: none"),
+            },
+            SensorState::Daylight(state) => match state.daylight() {
+                Some(daylight) => println!("daylight: {}", daylight),
+                None => println!("daylight: not configured"),
+            },
+            SensorState::GenericStatus(state) => println!("status: {}", state.status()),
+            SensorState::GenericFlag(state) => println!("flag: {}", state.flag()),
+            SensorState::Other(state) => println!("state: {}", state),
+        }
+        if let Some(battery) = sensor.config().battery() {
+            println!("battery: {}%", battery);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_bridge(client: &Client, m: &ArgMatches) -> Res<()> {
+    match m.subcommand() {
+        ("info", _) => run_bridge_info(client),
+        ("capacity", _) => run_bridge_capacity(client),
+        ("link", Some(sub_m)) => run_bridge_link(client, sub_m),
+        ("touchlink", _) => BridgeConfig::get_config(client)?.touchlink(),
+        ("set", Some(sub_m)) => run_bridge_set(client, sub_m),
+        (_, _) => Ok(()),
+    }
+}
+
+fn run_bridge_info(client: &Client) -> Res<()> {
+    let config = BridgeConfig::get_config(client)?;
+    println!("name: {}", config.name());
+    if let Some(bridgeid) = config.bridgeid() {
+        println!("bridge id: {}", bridgeid);
+    }
+    if let Some(modelid) = config.modelid() {
+        println!("model: {}", modelid);
+    }
+    println!(
+        "software: {} (api {})",
+        config.swversion(),
+        config.apiversion()
+    );
+    println!("mac: {}", config.mac());
+    println!(
+        "network: {}/{} via {}{}",
+        config.ipaddress(),
+        config.netmask(),
+        config.gateway(),
+        if config.dhcp() { " (dhcp)" } else { "" }
+    );
+    if config.proxyaddress() != "none" {
+        println!("proxy: {}:{}", config.proxyaddress(), config.proxyport());
+    }
+    println!("zigbee channel: {}", config.zigbeechannel());
+    println!("timezone: {}", config.timezone());
+    if let Some(localtime) = config.localtime() {
+        println!("local time: {}", localtime);
+    }
+    if let Some(portalconnection) = config.portalconnection() {
+        println!("portal: {}", portalconnection);
+    }
+    if let Some(services) = config.internetservices() {
+        println!("internet: {}", services.internet());
+        println!("remote access: {}", services.remoteaccess());
+    }
 
-    if let Ok(scenes) = scene_list {
-        let scene_list_yml = serde_yaml::to_string(&scenes).unwrap();
-        println!("{}", scene_list_yml);
-    } else if let Err(e) = scene_list {
-        return Result::Err(e.into());
+    Ok(())
+}
+
+fn run_bridge_capacity(client: &Client) -> Res<()> {
+    let capabilities = Capabilities::get_capabilities(client)?;
+    let resources = [
+        "lights",
+        "groups",
+        "scenes",
+        "lightstates",
+        "schedules",
+        "rules",
+        "conditions",
+        "actions",
+        "sensors",
+        "resourcelinks",
+    ];
+    for resource in resources.iter() {
+        if let Some(capacity) = capabilities.capacity(resource) {
+            println!(
+                "{}: {} of {} used, {} available",
+                resource,
+                capacity.used(),
+                capacity.total(),
+                capacity.available()
+            );
+        }
     }
 
     Ok(())
 }
 
-fn run_scene_power(client: &Client, m: &ArgMatches, power: bool) -> Res<()> {
+fn run_bridge_link(client: &Client, m: &ArgMatches) -> Res<()> {
+    let mut config = BridgeConfig::get_config(client)?;
+    config.press_link_button()?;
+    println!("link button pressed, pair the app within 30 seconds");
+    if m.is_present("wait") {
+        wait::wait_for_link_button(
+            config,
+            Duration::from_secs(1),
+            Duration::from_secs(60),
+            |_| {},
+        )?;
+        println!("pairing window closed");
+    }
+
     Ok(())
 }
+
+fn run_bridge_set(client: &Client, m: &ArgMatches) -> Res<()> {
+    let mut update = ConfigUpdateBuilder::default();
+    if let Some(name) = m.value_of("name") {
+        update.name(Some(name.to_owned()));
+    }
+    if let Some(timezone) = m.value_of("timezone") {
+        update.timezone(Some(timezone.to_owned()));
+    }
+    if m.is_present("zigbee-channel") {
+        update.zigbeechannel(Some(value_t!(m, "zigbee-channel", u8)?));
+    }
+    BridgeConfig::get_config(client)?.update(&update.build()?)?;
+
+    Ok(())
+}
+
+fn run_users(client: &Client, m: &ArgMatches) -> Res<()> {
+    match m.subcommand() {
+        ("list", _) => run_users_list(client),
+        ("delete", Some(sub_m)) => run_users_delete(client, sub_m),
+        ("prune", Some(sub_m)) => run_users_prune(client, sub_m),
+        (_, _) => Ok(()),
+    }
+}
+
+fn run_users_list(client: &Client) -> Res<()> {
+    let config = BridgeConfig::get_config(client)?;
+    for (key, user) in config.whitelist() {
+        let current = if Some(key.as_str()) == client.username() {
+            " (current)"
+        } else {
+            ""
+        };
+        println!(
+            "{} {}{}: created {}, last used {}",
+            key,
+            user.name(),
+            current,
+            timestamp::format(user.create_date()),
+            timestamp::format(user.last_use_date())
+        );
+    }
+
+    Ok(())
+}
+
+fn run_users_delete(client: &Client, m: &ArgMatches) -> Res<()> {
+    let mut config = BridgeConfig::get_config(client)?;
+    for key in m.values_of("key").unwrap() {
+        config.delete_user(key)?;
+    }
+
+    Ok(())
+}
+
+fn run_users_prune(client: &Client, m: &ArgMatches) -> Res<()> {
+    let unused_for = humantime::parse_duration(m.value_of("unused-for").unwrap())?;
+    let mut config = BridgeConfig::get_config(client)?;
+    let keys: Vec<String> = config
+        .unused_users(unused_for)?
+        .into_iter()
+        .map(|key| key.to_owned())
+        .collect();
+    for key in &keys {
+        let user = &config.whitelist()[key];
+        println!(
+            "{} {}: last used {}",
+            key,
+            user.name(),
+            timestamp::format(user.last_use_date())
+        );
+    }
+    if keys.is_empty() || m.is_present("dry-run") {
+        println!("{} users would be deleted", keys.len());
+        return Ok(());
+    }
+    if !m.is_present("yes") && !confirm(&format!("delete {} users?", keys.len()))? {
+        println!("no users deleted");
+        return Ok(());
+    }
+    for key in &keys {
+        config.delete_user(key)?;
+    }
+    println!("{} users deleted", keys.len());
+
+    Ok(())
+}
+
+/// Ask a yes/no question on the terminal, anything but `y` or `yes` is no.
+fn confirm(question: &str) -> Res<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
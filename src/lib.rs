@@ -1,34 +1,26 @@
-use std::error::Error;
+//! HTTP transport layer for the Philips Hue API.
+//!
+//! `Client` implements the `HTTPClient` trait of the `hue-core` crate over
+//! HTTPS, and the `wait` module polls the bridge until a condition holds. The
+//! models of `hue-core` are re-exported here.
+
 use std::time::Duration;
-pub mod groups;
-pub mod lights;
-pub mod scenes;
 
+extern crate hue_core;
+#[cfg(test)]
 #[macro_use]
 extern crate lazy_static;
 extern crate reqwest;
-extern crate serde;
 #[macro_use]
 extern crate serde_derive;
-#[macro_use]
-extern crate serde_json;
-extern crate custom_derive;
-#[macro_use]
-extern crate derive_builder;
-extern crate rand;
+extern crate toml;
+
+pub use hue_core::*;
 
 mod settings;
+pub mod wait;
 use settings::Settings;
 
-pub type Res<T> = Result<T, Box<dyn Error>>;
-
-pub trait HTTPClient {
-    fn get(&self, call: &str) -> Res<String>;
-    fn post(&self, call: &str, body: String) -> Res<String>;
-    fn put(&self, call: &str, body: String) -> Res<String>;
-    fn delete(&self, call: &str) -> Res<String>;
-}
-
 #[derive(Debug)]
 pub struct Client {
     settings: Settings,
@@ -57,12 +49,6 @@ impl Client {
     }
 
     fn rest_call_url(&self, suffix: &str) -> String {
-        println!(
-            "https://{}/api/{}/{}",
-            self.settings.ip(),
-            self.settings.username(),
-            suffix
-        );
         format!(
             "https://{}/api/{}/{}",
             self.settings.ip(),
@@ -106,39 +92,8 @@ impl HTTPClient for Client {
             .send()?
             .text()?)
     }
-}
 
-mod test_common {
-
-    use super::*;
-
-    #[derive(Default, Debug)]
-    pub struct HTTPClientMock {
-        pub body: Option<String>,
-        pub return_string: Option<String>,
-        pub error: Option<String>,
-    }
-
-    impl HTTPClient for HTTPClientMock {
-        fn get(&self, _: &str) -> Res<String> {
-            if let Some(s) = self.return_string.as_ref() {
-                Ok(s.to_owned())
-            } else {
-                let e_str = self.error.as_ref().unwrap().clone();
-                Err(e_str.into())
-            }
-        }
-
-        fn post(&self, _: &str, _: String) -> Res<String> {
-            Ok("".to_owned())
-        }
-
-        fn put(&self, _: &str, _: String) -> Res<String> {
-            Ok("".to_owned())
-        }
-
-        fn delete(&self, _: &str) -> Res<String> {
-            Ok("".to_owned())
-        }
+    fn username(&self) -> Option<&str> {
+        Some(self.settings.username())
     }
 }
@@ -25,7 +25,7 @@ impl Settings {
         let default_config_file = env::var("HOME")? + "/.huerc.toml";
         let config = match config_file {
             Some(file_name) => read_to_string(Path::new(file_name))
-                .expect(format!("File {} doesn't exists", file_name).as_ref()),
+                .unwrap_or_else(|_| panic!("File {} doesn't exists", file_name)),
             None => read_to_string(Path::new(&default_config_file)).unwrap_or("".to_owned()),
         };
         let mut settings = toml::from_str(&config).unwrap_or(Settings::default());
@@ -57,11 +57,12 @@ impl Settings {
     }
 }
 
+#[cfg(test)]
 mod tests {
 
     use super::*;
     use std::fs::{remove_file, File};
-    use std::io::{Read, Write};
+    use std::io::Write;
     use std::sync::Mutex;
 
     lazy_static! {
@@ -105,7 +106,7 @@ mod tests {
         f.write_all(cfg.as_bytes()).unwrap();
         let settings = Settings::new(Some(&tmp_file.to_owned())).unwrap();
 
-        assert_eq!(settings.debug, false);
+        assert!(!settings.debug);
         assert_eq!(settings.hue.ip, "192.168.2.42");
         assert_eq!(settings.hue.username, "huehue");
 
@@ -133,7 +134,7 @@ mod tests {
         let settings = Settings::new(Some(&tmp_file.to_owned())).unwrap();
         assert_eq!(settings.ip(), "10.10.10.10");
         assert_eq!(settings.username(), "HueGrant");
-        assert_eq!(settings.debug, true);
+        assert!(settings.debug);
 
         remove_file(p).unwrap();
         env::remove_var("HUE_IP");
@@ -0,0 +1,225 @@
+//! Waiting for the bridge by polling it, blocking the calling thread.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use hue_core::config::BridgeConfig;
+use hue_core::firmware::Firmware;
+use hue_core::{HTTPClient, Res};
+
+/// Refresh `value` with `poll` every `interval` until it is `done`.
+///
+/// Fails with the message of `timed_out` for the last value after `timeout`.
+pub fn poll_until<T, P, D, M>(
+    mut value: T,
+    interval: Duration,
+    timeout: Duration,
+    mut poll: P,
+    done: D,
+    timed_out: M,
+) -> Res<T>
+where
+    P: FnMut(T) -> Res<T>,
+    D: Fn(&T) -> bool,
+    M: FnOnce(&T) -> String,
+{
+    let start = Instant::now();
+    loop {
+        value = poll(value)?;
+        if done(&value) {
+            return Ok(value);
+        }
+        if start.elapsed() >= timeout {
+            return Err(timed_out(&value).into());
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Poll the bridge until the link button window is closed.
+///
+/// `progress` is called with every polled configuration.
+pub fn wait_for_link_button<'a, C, F>(
+    config: BridgeConfig<'a, C>,
+    interval: Duration,
+    timeout: Duration,
+    mut progress: F,
+) -> Res<BridgeConfig<'a, C>>
+where
+    C: HTTPClient + Default,
+    F: FnMut(&BridgeConfig<'a, C>),
+{
+    poll_until(
+        config,
+        interval,
+        timeout,
+        |config| {
+            let config = config.refresh()?;
+            progress(&config);
+            Ok(config)
+        },
+        |config| !config.linkbutton(),
+        |_| "timed out, link button is still pressed".to_owned(),
+    )
+}
+
+/// Poll the bridge until it finished checking for updates.
+pub fn wait_for_check<'a, C, F>(
+    firmware: Firmware<'a, C>,
+    interval: Duration,
+    timeout: Duration,
+    progress: F,
+) -> Res<Firmware<'a, C>>
+where
+    C: HTTPClient + Default,
+    F: FnMut(&Firmware<'a, C>),
+{
+    wait_for_firmware(firmware, interval, timeout, progress, |firmware| {
+        !firmware.checkforupdate()
+    })
+}
+
+/// Poll the bridge until there are no more updates to install.
+///
+/// `progress` is called with every polled status.
+pub fn wait_for_install<'a, C, F>(
+    firmware: Firmware<'a, C>,
+    interval: Duration,
+    timeout: Duration,
+    progress: F,
+) -> Res<Firmware<'a, C>>
+where
+    C: HTTPClient + Default,
+    F: FnMut(&Firmware<'a, C>),
+{
+    wait_for_firmware(firmware, interval, timeout, progress, |firmware| {
+        !firmware.state().pending() && !firmware.bridge().state().pending()
+    })
+}
+
+fn wait_for_firmware<'a, C, F, D>(
+    firmware: Firmware<'a, C>,
+    interval: Duration,
+    timeout: Duration,
+    mut progress: F,
+    done: D,
+) -> Res<Firmware<'a, C>>
+where
+    C: HTTPClient + Default,
+    F: FnMut(&Firmware<'a, C>),
+    D: Fn(&Firmware<'a, C>) -> bool,
+{
+    poll_until(
+        firmware,
+        interval,
+        timeout,
+        |firmware| {
+            let firmware = firmware.refresh()?;
+            progress(&firmware);
+            Ok(firmware)
+        },
+        done,
+        |firmware| format!("timed out, update state is {}", firmware.state()),
+    )
+}
+
+#[cfg(test)]
+mod tests_wait {
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        {
+            "swupdate2": {
+                "checkforupdate": false,
+                "lastchange": "2019-03-13T07:17:10",
+                "bridge": {"state": "noupdates", "lastinstall": "2019-03-07T10:47:47"},
+                "state": "anyreadytoinstall",
+                "autoinstall": {"updatetime": "T14:00:00", "on": true}
+            }
+        }"#;
+
+    /// Bridge answering every GET with `CONFIG`, waiting never writes.
+    #[derive(Debug, Default)]
+    struct BridgeMock;
+
+    impl HTTPClient for BridgeMock {
+        fn get(&self, _call: &str) -> Res<String> {
+            Ok(CONFIG.to_owned())
+        }
+        fn post(&self, call: &str, _body: String) -> Res<String> {
+            Err(format!("unexpected POST {}", call).into())
+        }
+        fn put(&self, call: &str, _body: String) -> Res<String> {
+            Err(format!("unexpected PUT {}", call).into())
+        }
+        fn delete(&self, call: &str) -> Res<String> {
+            Err(format!("unexpected DELETE {}", call).into())
+        }
+    }
+
+    #[test]
+    fn poll_until_done() {
+        let mut polls = 0;
+        let value = poll_until(
+            0,
+            Duration::from_millis(1),
+            Duration::from_secs(1),
+            |value| {
+                polls += 1;
+                Ok(value + 1)
+            },
+            |value| *value == 3,
+            |_| "timed out".to_owned(),
+        )
+        .unwrap();
+        assert_eq!(value, 3);
+        assert_eq!(polls, 3);
+
+        let error = poll_until(
+            0,
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+            |value| Ok(value + 1),
+            |_| false,
+            |value| format!("timed out at {}", value),
+        )
+        .unwrap_err();
+        assert!(error.to_string().starts_with("timed out at"));
+
+        let result = poll_until(
+            0,
+            Duration::from_millis(1),
+            Duration::from_secs(1),
+            |_| Err("unreachable".into()),
+            |_| true,
+            |_| "timed out".to_owned(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn wait_for_firmware_updates() {
+        let client = BridgeMock;
+        let mut polls = 0;
+        let firmware = Firmware::get_firmware(&client).unwrap();
+        wait_for_check(
+            firmware,
+            Duration::from_millis(1),
+            Duration::from_secs(1),
+            |_| polls += 1,
+        )
+        .unwrap();
+        assert_eq!(polls, 1);
+
+        let firmware = Firmware::get_firmware(&client).unwrap();
+        let error = wait_for_install(
+            firmware,
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+            |_| {},
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("anyreadytoinstall"));
+    }
+}